@@ -0,0 +1,773 @@
+use std::fmt::Display;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use crate::NetworkError;
+
+/// # Mac Addr
+/// A 48 bit MAC address, like `52:54:00:12:34:56`.
+/// It can be parsed from the colon (`52:54:00:12:34:56`), dash
+/// (`52-54-00-12-34-56`) and Cisco dotted (`5254.0012.3456`) forms,
+/// in any case, and is displayed in the lowercase colon form.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::MacAddr;
+///
+/// let mac: MacAddr = "5254.0012.3456".parse().unwrap();
+///
+/// assert_eq!(mac.to_string(), "52:54:00:12:34:56");
+/// assert_eq!(mac.to_dash_string(), "52-54-00-12-34-56");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MacAddr([u8; 6]);
+
+impl MacAddr {
+    /// Creates a MAC address from its six bytes.
+    pub fn new(octets: [u8; 6]) -> MacAddr {
+        MacAddr(octets)
+    }
+
+    /// The six bytes of the MAC address.
+    pub fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+
+    /// The first three bytes, which identify the vendor.
+    pub fn oui(&self) -> [u8; 3] {
+        [self.0[0], self.0[1], self.0[2]]
+    }
+
+    /// Whether this is a multicast (or broadcast) address.
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+
+    /// Whether the address was assigned locally instead of by
+    /// the vendor, like the random addresses of veth interfaces.
+    pub fn is_local(&self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+
+    /// Formats the address in the dash form, like `52-54-00-12-34-56`.
+    pub fn to_dash_string(&self) -> String {
+        self.to_string().replace(':', "-")
+    }
+
+    /// Formats the address in the Cisco dotted form, like `5254.0012.3456`.
+    pub fn to_cisco_string(&self) -> String {
+        self.0
+            .chunks(2)
+            .map(|x| format!("{:02x}{:02x}", x[0], x[1]))
+            .collect::<Vec<String>>()
+            .join(".")
+    }
+}
+
+impl From<[u8; 6]> for MacAddr {
+    fn from(octets: [u8; 6]) -> Self {
+        MacAddr(octets)
+    }
+}
+
+impl FromStr for MacAddr {
+    type Err = NetworkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || NetworkError::Parse(format!("invalid MAC address {:?}", s));
+
+        let hex = match s.len() {
+            17 if s.split(':').all(|x| x.len() == 2) => s.replace(':', ""),
+            17 if s.split('-').all(|x| x.len() == 2) => s.replace('-', ""),
+            14 if s.split('.').all(|x| x.len() == 4) => s.replace('.', ""),
+            _ => return Err(error()),
+        };
+        if hex.len() != 12 || !hex.chars().all(|x| x.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+
+        let mut octets = [0u8; 6];
+        for (i, octet) in octets.iter_mut().enumerate() {
+            *octet = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| error())?;
+        }
+
+        Ok(MacAddr(octets))
+    }
+}
+
+impl Display for MacAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            a, b, c, d, e, g
+        )
+    }
+}
+
+/// # Netmask
+/// An IPv4 netmask, like `255.255.255.0`.
+/// Only contiguous netmasks are valid, so it can always be
+/// converted to and from a prefix length.
+/// It can be parsed from the dotted form or from the hex
+/// form BSD `ifconfig` prints, like `0xffffff00`.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::Netmask;
+///
+/// let netmask: Netmask = "255.255.255.0".parse().unwrap();
+///
+/// assert_eq!(netmask.prefix_len(), 24);
+/// assert_eq!(Netmask::from_prefix_len(24), Some(netmask));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Netmask(Ipv4Addr);
+
+impl Netmask {
+    /// Creates the netmask for a prefix length, like
+    /// `255.255.255.0` for 24. Returns `None` above 32.
+    pub fn from_prefix_len(prefix_len: u8) -> Option<Netmask> {
+        if prefix_len > 32 {
+            return None;
+        }
+
+        Some(Netmask(Ipv4Addr::from(
+            u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0),
+        )))
+    }
+
+    /// The prefix length of the netmask, like 24 for `255.255.255.0`.
+    pub fn prefix_len(&self) -> u8 {
+        u32::from(self.0).count_ones() as u8
+    }
+
+    /// The netmask as an address.
+    pub fn addr(&self) -> Ipv4Addr {
+        self.0
+    }
+}
+
+impl TryFrom<Ipv4Addr> for Netmask {
+    type Error = NetworkError;
+
+    fn try_from(addr: Ipv4Addr) -> Result<Self, Self::Error> {
+        let bits = u32::from(addr);
+        if bits.leading_ones() + bits.trailing_zeros() != 32 {
+            return Err(NetworkError::Parse(format!(
+                "{} is not a contiguous netmask",
+                addr
+            )));
+        }
+
+        Ok(Netmask(addr))
+    }
+}
+
+impl From<Netmask> for Ipv4Addr {
+    fn from(netmask: Netmask) -> Self {
+        netmask.0
+    }
+}
+
+impl FromStr for Netmask {
+    type Err = NetworkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let addr = match s.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16).map(Ipv4Addr::from).ok(),
+            None => s.parse().ok(),
+        };
+
+        match addr {
+            Some(addr) => Netmask::try_from(addr),
+            None => Err(NetworkError::Parse(format!("invalid netmask {:?}", s))),
+        }
+    }
+}
+
+impl Display for Netmask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// # Cidr
+/// A block of IPv4 or IPv6 addresses, like `10.0.0.0/8` or
+/// `fd00::/8`, written as an address and a prefix length.
+/// The bits of the address after the prefix do not matter, so
+/// `10.0.0.5/24` is the same block as `10.0.0.0/24`.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::Cidr;
+///
+/// let cidr: Cidr = "192.168.0.0/16".parse().unwrap();
+///
+/// assert!(cidr.contains(&"192.168.1.20".parse().unwrap()));
+/// assert!(!cidr.contains(&"10.0.0.5".parse().unwrap()));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// Creates the block of an address and a prefix length.
+    /// Returns `None` if the prefix length is longer than the
+    /// address, like above 32 for IPv4.
+    pub fn new(address: IpAddr, prefix_len: u8) -> Option<Cidr> {
+        let max = match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        if prefix_len > max {
+            return None;
+        }
+
+        Some(Cidr {
+            address,
+            prefix_len,
+        })
+    }
+
+    /// The address the block was created with.
+    pub fn address(&self) -> IpAddr {
+        self.address
+    }
+
+    /// The length of the prefix, like 8 for `10.0.0.0/8`.
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Whether an address is in the block. An address of
+    /// the other family never is.
+    pub fn contains(&self, address: &IpAddr) -> bool {
+        match (self.address, address) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(*address) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(*address) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = NetworkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || NetworkError::Parse(format!("invalid CIDR block {:?}", s));
+
+        let (address, prefix_len) = s.split_once('/').ok_or_else(error)?;
+        let address = address.parse().map_err(|_| error())?;
+        let prefix_len = prefix_len.parse().map_err(|_| error())?;
+
+        Cidr::new(address, prefix_len).ok_or_else(error)
+    }
+}
+
+impl Display for Cidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_len)
+    }
+}
+
+/// # Inet Address
+/// Represents an IPv4 address assigned to a network interface.
+/// An interface can have any number of these, the first one is
+/// the primary address that is also stored in `Network::inet`.
+/// * address: The IPv4 address.
+/// * prefix_len: The length of the network prefix, like 24 for a /24.
+/// * broadcast: The broadcast address, if there is one.
+/// * peer: The address of the other end of a point-to-point link.
+/// * scope: The scope of the address, like global or host.
+/// * secondary: Whether `ip` lists it as a secondary address of its subnet.
+/// * flags: The other `IFA_F_*` flags, like `noprefixroute`.
+/// * label: The label of the address, like `eth0:1`, if the source reports it.
+/// * valid_lft: How long the address stays valid, if the source reports it.
+/// * preferred_lft: How long the address stays preferred, if the source reports it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InetAddress {
+    pub address: Ipv4Addr,
+    pub prefix_len: u8,
+    pub broadcast: Option<Ipv4Addr>,
+    pub peer: Option<Ipv4Addr>,
+    pub scope: Scope,
+    pub secondary: bool,
+    pub flags: AddressFlags,
+    pub label: Option<String>,
+    pub valid_lft: Option<Lifetime>,
+    pub preferred_lft: Option<Lifetime>,
+}
+
+impl InetAddress {
+    /// Creates an address with nothing but the address and prefix
+    /// length, and the scope worked out from the address.
+    pub fn new(address: Ipv4Addr, prefix_len: u8) -> InetAddress {
+        InetAddress {
+            address,
+            prefix_len,
+            broadcast: None,
+            peer: None,
+            scope: Scope::of_ipv4(&address),
+            secondary: false,
+            flags: AddressFlags::default(),
+            label: None,
+            valid_lft: None,
+            preferred_lft: None,
+        }
+    }
+
+    /// Whether the address has a limited lifetime, like one from DHCP.
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self.valid_lft, Some(Lifetime::Seconds(_)))
+    }
+
+    /// The netmask of the address, like `255.255.255.0` for a /24.
+    pub fn netmask(&self) -> Netmask {
+        Netmask::from_prefix_len(self.prefix_len.min(32)).expect("prefix length is at most 32")
+    }
+}
+
+impl Display for InetAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_len)?;
+
+        if let Some(broadcast) = &self.broadcast {
+            write!(f, " broadcast {}", broadcast)?;
+        }
+
+        if let Some(peer) = &self.peer {
+            write!(f, " peer {}", peer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// # Scope
+/// The scope of an address, which is how far away it is valid.
+/// * Global: Valid everywhere.
+/// * Site: Only valid inside the site, like the deprecated `fec0::/10`.
+/// * Link: Only valid on the link, like `fe80::/10`.
+/// * Host: Only valid on this host, like `::1`.
+/// * Other: Any other scope, with its number as used by the kernel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Global,
+    Site,
+    Link,
+    Host,
+    Other(u8),
+}
+
+impl Scope {
+    /// Gets the scope from the `ifa_scope` of a netlink address,
+    /// which uses the `RT_SCOPE_*` numbers.
+    pub(crate) fn from_rt_scope(scope: u8) -> Scope {
+        match scope {
+            0 => Scope::Global,
+            200 => Scope::Site,
+            253 => Scope::Link,
+            254 => Scope::Host,
+            other => Scope::Other(other),
+        }
+    }
+
+    /// Gets the scope from a `scopeid 0x20<link>` as printed by
+    /// Linux `ifconfig`, using the name if there is one.
+    pub(crate) fn from_scopeid(scopeid: &str) -> Option<Scope> {
+        if let Some((_, name)) = scopeid.split_once('<') {
+            return match name.trim_end_matches('>') {
+                "global" => Some(Scope::Global),
+                "site" => Some(Scope::Site),
+                "link" => Some(Scope::Link),
+                "host" => Some(Scope::Host),
+                _ => None,
+            };
+        }
+
+        match u32::from_str_radix(scopeid.trim_start_matches("0x"), 16).ok()? {
+            0x00 => Some(Scope::Global),
+            0x10 => Some(Scope::Host),
+            0x20 => Some(Scope::Link),
+            0x40 => Some(Scope::Site),
+            _ => None,
+        }
+    }
+
+    /// Gets the scope from the name `ip` prints for it, like `link`.
+    pub(crate) fn from_name(name: &str) -> Option<Scope> {
+        match name {
+            "global" | "universe" => Some(Scope::Global),
+            "site" => Some(Scope::Site),
+            "link" => Some(Scope::Link),
+            "host" => Some(Scope::Host),
+            other => other.parse().ok().map(Scope::from_rt_scope),
+        }
+    }
+
+    /// Works out the scope of an IPv4 address from the address itself,
+    /// the same way the kernel does when none is given.
+    pub fn of_ipv4(address: &Ipv4Addr) -> Scope {
+        if address.is_loopback() {
+            Scope::Host
+        } else {
+            Scope::Global
+        }
+    }
+
+    /// Works out the scope of an IPv6 address from the address itself.
+    pub fn of(address: &Ipv6Addr) -> Scope {
+        let first = address.segments()[0];
+
+        if address.is_loopback() {
+            Scope::Host
+        } else if first & 0xffc0 == 0xfe80 {
+            Scope::Link
+        } else if first & 0xffc0 == 0xfec0 {
+            Scope::Site
+        } else {
+            Scope::Global
+        }
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scope::Global => write!(f, "global"),
+            Scope::Site => write!(f, "site"),
+            Scope::Link => write!(f, "link"),
+            Scope::Host => write!(f, "host"),
+            Scope::Other(scope) => write!(f, "{}", scope),
+        }
+    }
+}
+
+/// # Address Flags
+/// The flags of an IPv4 or IPv6 address, like whether it is a
+/// temporary privacy address or still going through duplicate
+/// address detection. The values are the same as the kernel's
+/// `IFA_F_*`, which it shares between IPv4 and IPv6, though most
+/// of them, like `temporary`, are only set on IPv6 addresses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AddressFlags(u32);
+
+/// The name `AddressFlags` had when it was only used for IPv6.
+pub type Inet6Flags = AddressFlags;
+
+impl AddressFlags {
+    pub const TEMPORARY: AddressFlags = AddressFlags(0x01);
+    pub const NODAD: AddressFlags = AddressFlags(0x02);
+    pub const OPTIMISTIC: AddressFlags = AddressFlags(0x04);
+    pub const DADFAILED: AddressFlags = AddressFlags(0x08);
+    pub const HOMEADDRESS: AddressFlags = AddressFlags(0x10);
+    pub const DEPRECATED: AddressFlags = AddressFlags(0x20);
+    pub const TENTATIVE: AddressFlags = AddressFlags(0x40);
+    pub const PERMANENT: AddressFlags = AddressFlags(0x80);
+    pub const MANAGETEMPADDR: AddressFlags = AddressFlags(0x100);
+    pub const NOPREFIXROUTE: AddressFlags = AddressFlags(0x200);
+    pub const STABLE_PRIVACY: AddressFlags = AddressFlags(0x800);
+
+    const NAMES: [(AddressFlags, &'static str); 11] = [
+        (AddressFlags::TEMPORARY, "temporary"),
+        (AddressFlags::NODAD, "nodad"),
+        (AddressFlags::OPTIMISTIC, "optimistic"),
+        (AddressFlags::DADFAILED, "dadfailed"),
+        (AddressFlags::HOMEADDRESS, "home"),
+        (AddressFlags::DEPRECATED, "deprecated"),
+        (AddressFlags::TENTATIVE, "tentative"),
+        (AddressFlags::PERMANENT, "permanent"),
+        (AddressFlags::MANAGETEMPADDR, "mngtmpaddr"),
+        (AddressFlags::NOPREFIXROUTE, "noprefixroute"),
+        (AddressFlags::STABLE_PRIVACY, "stable-privacy"),
+    ];
+
+    /// Creates the flags from their raw `IFA_F_*` bits.
+    pub fn from_bits(bits: u32) -> AddressFlags {
+        AddressFlags(bits)
+    }
+
+    /// The raw `IFA_F_*` bits of the flags.
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Gets a flag from the name `ip` and BSD `ifconfig` print
+    /// for it, like `temporary` or `tentative`.
+    pub fn from_name(name: &str) -> Option<AddressFlags> {
+        AddressFlags::NAMES
+            .iter()
+            .find(|(_, x)| *x == name)
+            .map(|(flag, _)| *flag)
+    }
+
+    /// Whether all of the given flags are set.
+    pub fn contains(&self, other: AddressFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Sets the given flags.
+    pub fn insert(&mut self, other: AddressFlags) {
+        self.0 |= other.0;
+    }
+
+    /// Whether no flag is set.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl std::ops::BitOr for AddressFlags {
+    type Output = AddressFlags;
+
+    fn bitor(self, other: AddressFlags) -> AddressFlags {
+        AddressFlags(self.0 | other.0)
+    }
+}
+
+impl Display for AddressFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = AddressFlags::NAMES
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect::<Vec<&str>>();
+
+        write!(f, "{}", names.join(" "))
+    }
+}
+
+/// # Lifetime
+/// How long an address stays valid or preferred, as printed by
+/// `ip` after `valid_lft` and `preferred_lft`.
+/// * Forever: The address never expires, like a static one.
+/// * Seconds: The number of seconds left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Lifetime {
+    Forever,
+    Seconds(u32),
+}
+
+impl Lifetime {
+    /// Gets the lifetime from the seconds the kernel reports,
+    /// where `0xffffffff` means forever.
+    pub(crate) fn from_secs(secs: u32) -> Lifetime {
+        match secs {
+            u32::MAX => Lifetime::Forever,
+            secs => Lifetime::Seconds(secs),
+        }
+    }
+}
+
+impl FromStr for Lifetime {
+    type Err = NetworkError;
+
+    /// Parses `forever` or a number of seconds like `86133sec`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "forever" {
+            return Ok(Lifetime::Forever);
+        }
+
+        s.trim_end_matches("sec")
+            .parse()
+            .map(Lifetime::Seconds)
+            .map_err(|_| NetworkError::Parse(format!("invalid lifetime {:?}", s)))
+    }
+}
+
+impl Display for Lifetime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Lifetime::Forever => write!(f, "forever"),
+            Lifetime::Seconds(secs) => write!(f, "{}sec", secs),
+        }
+    }
+}
+
+/// # Inet6 Address
+/// Represents an IPv6 address assigned to a network interface.
+/// * address: The IPv6 address.
+/// * prefix_len: The length of the network prefix, like 64 for a /64.
+/// * scope: The scope of the address, like link or global.
+/// * flags: The flags of the address, if the source reports them.
+/// * valid_lft: How long the address stays valid, if the source reports it.
+/// * preferred_lft: How long the address stays preferred, if the source reports it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inet6Address {
+    pub address: Ipv6Addr,
+    pub prefix_len: u8,
+    pub scope: Scope,
+    pub flags: AddressFlags,
+    pub valid_lft: Option<Lifetime>,
+    pub preferred_lft: Option<Lifetime>,
+}
+
+impl Inet6Address {
+    /// Creates an address with nothing but the address and prefix
+    /// length, and the scope worked out from the address.
+    pub fn new(address: Ipv6Addr, prefix_len: u8) -> Inet6Address {
+        Inet6Address {
+            address,
+            prefix_len,
+            scope: Scope::of(&address),
+            flags: AddressFlags::default(),
+            valid_lft: None,
+            preferred_lft: None,
+        }
+    }
+
+    /// Whether the address has a limited lifetime, like one from
+    /// router advertisements.
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self.valid_lft, Some(Lifetime::Seconds(_)))
+    }
+
+    /// Whether this is a temporary privacy address.
+    pub fn is_temporary(&self) -> bool {
+        self.flags.contains(AddressFlags::TEMPORARY)
+    }
+
+    /// Whether the preferred lifetime of the address is over.
+    pub fn is_deprecated(&self) -> bool {
+        self.flags.contains(AddressFlags::DEPRECATED)
+    }
+
+    /// Whether the address is still going through duplicate
+    /// address detection.
+    pub fn is_tentative(&self) -> bool {
+        self.flags.contains(AddressFlags::TENTATIVE)
+    }
+}
+
+impl Display for Inet6Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} scope {}",
+            self.address, self.prefix_len, self.scope
+        )?;
+
+        if !self.flags.is_empty() {
+            write!(f, " {}", self.flags)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_netmask_work() {
+        assert_eq!(
+            Netmask::from_prefix_len(24).map(Ipv4Addr::from),
+            Some(Ipv4Addr::new(255, 255, 255, 0))
+        );
+        assert_eq!(Netmask::from_prefix_len(0).unwrap().to_string(), "0.0.0.0");
+        assert_eq!(Netmask::from_prefix_len(33), None);
+        assert_eq!("255.255.240.0".parse::<Netmask>().unwrap().prefix_len(), 20);
+        assert_eq!("0xffffff00".parse::<Netmask>().unwrap().prefix_len(), 24);
+        assert!("255.0.255.0".parse::<Netmask>().is_err());
+        assert!("".parse::<Netmask>().is_err());
+    }
+
+    #[test]
+    fn does_mac_addr_work() {
+        let mac = MacAddr::new([0x52, 0x54, 0x00, 0x12, 0x34, 0x56]);
+
+        assert_eq!("52:54:00:12:34:56".parse::<MacAddr>().unwrap(), mac);
+        assert_eq!("52-54-00-12-34-56".parse::<MacAddr>().unwrap(), mac);
+        assert_eq!("5254.0012.3456".parse::<MacAddr>().unwrap(), mac);
+        assert_eq!(
+            "52:54:00:12:34:56"
+                .to_uppercase()
+                .parse::<MacAddr>()
+                .unwrap(),
+            mac
+        );
+        assert_eq!(mac.to_cisco_string(), "5254.0012.3456");
+        assert_eq!(mac.oui(), [0x52, 0x54, 0x00]);
+        assert!(mac.is_local());
+        assert!("52:54:00:12:34".parse::<MacAddr>().is_err());
+        assert!("52:54-00:12:34:56".parse::<MacAddr>().is_err());
+        assert!("zz:54:00:12:34:56".parse::<MacAddr>().is_err());
+    }
+
+    #[test]
+    fn does_cidr_work() {
+        let cidr = "10.0.0.5/8".parse::<Cidr>().unwrap();
+        assert_eq!(cidr.prefix_len(), 8);
+        assert_eq!(cidr.to_string(), "10.0.0.5/8");
+        assert!(cidr.contains(&"10.200.3.4".parse().unwrap()));
+        assert!(!cidr.contains(&"11.0.0.1".parse().unwrap()));
+        assert!(!cidr.contains(&"::1".parse().unwrap()));
+
+        let cidr = "fd00::/8".parse::<Cidr>().unwrap();
+        assert!(cidr.contains(&"fd12:3456::1".parse().unwrap()));
+        assert!(!cidr.contains(&"fe80::1".parse().unwrap()));
+
+        let all = "0.0.0.0/0".parse::<Cidr>().unwrap();
+        assert!(all.contains(&"192.168.1.20".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+        assert!("10.0.0.0".parse::<Cidr>().is_err());
+        assert!("fd00::/x".parse::<Cidr>().is_err());
+    }
+
+    #[test]
+    fn does_scope_work() {
+        assert_eq!(Scope::from_scopeid("0x20<link>"), Some(Scope::Link));
+        assert_eq!(Scope::from_scopeid("0x10"), Some(Scope::Host));
+        assert_eq!(Scope::of(&"fe80::1".parse().unwrap()), Scope::Link);
+        assert_eq!(Scope::of(&"2001:db8::1".parse().unwrap()), Scope::Global);
+        assert_eq!(Scope::from_rt_scope(253), Scope::Link);
+        assert_eq!(Scope::from_name("host"), Some(Scope::Host));
+        assert_eq!(Scope::of_ipv4(&"127.0.0.1".parse().unwrap()), Scope::Host);
+    }
+
+    #[test]
+    fn does_lifetime_work() {
+        assert_eq!("forever".parse::<Lifetime>().unwrap(), Lifetime::Forever);
+        assert_eq!(
+            "86133sec".parse::<Lifetime>().unwrap(),
+            Lifetime::Seconds(86133)
+        );
+        assert_eq!(Lifetime::from_secs(u32::MAX), Lifetime::Forever);
+        assert_eq!(Lifetime::Seconds(5).to_string(), "5sec");
+        assert!("soon".parse::<Lifetime>().is_err());
+    }
+
+    #[test]
+    fn does_inet6_display_work() {
+        let address = Inet6Address {
+            flags: AddressFlags::TEMPORARY | AddressFlags::DEPRECATED,
+            ..Inet6Address::new("2001:db8::5".parse().unwrap(), 64)
+        };
+
+        assert!(address.is_temporary());
+        assert!(!address.is_tentative());
+        assert_eq!(
+            address.to_string(),
+            "2001:db8::5/64 scope global temporary deprecated"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! # Fixture
+//! A backend that serves networks that were handed to it,
+//! instead of reading them from the host.
+
+use crate::backend::Backend;
+use crate::parser::split_interfaces;
+use crate::{parse_network, Network, NetworkError};
+#[cfg(test)]
+use crate::{InterfaceFlags, InterfaceKind};
+
+/// # Fixture Backend
+/// A backend that always returns the same networks.
+/// This is meant for tests, so code that uses this crate can be
+/// tested without depending on the interfaces of the host.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::{get_networks_with, FixtureBackend, Network};
+///
+/// let backend = FixtureBackend::new(vec![Network {
+///     name: "eth0".to_string(),
+///     inet: Some("10.0.0.5".to_string()),
+///     ..Default::default()
+/// }]);
+///
+/// assert_eq!(get_networks_with(&backend)[0].name, "eth0");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FixtureBackend {
+    networks: Vec<Network>,
+    default_routes: Vec<String>,
+}
+
+impl FixtureBackend {
+    /// Creates a backend that returns the given networks.
+    pub fn new(networks: Vec<Network>) -> FixtureBackend {
+        FixtureBackend {
+            networks,
+            default_routes: Vec::new(),
+        }
+    }
+
+    /// Makes the backend report a default route on the interfaces
+    /// with the given names. There are none unless this is used.
+    pub fn with_default_routes(mut self, names: &[&str]) -> FixtureBackend {
+        self.default_routes = names.iter().map(|x| x.to_string()).collect();
+        self
+    }
+
+    /// Creates a backend from text captured from `ifconfig`,
+    /// parsing every interface with `parse_network`.
+    pub fn from_ifconfig(text: &str) -> FixtureBackend {
+        FixtureBackend::new(
+            split_interfaces(text)
+                .into_iter()
+                .map(parse_network)
+                .collect(),
+        )
+    }
+}
+
+impl Backend for FixtureBackend {
+    fn name(&self) -> &str {
+        "fixture"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn networks(&self) -> Result<Vec<Network>, NetworkError> {
+        Ok(self.networks.clone())
+    }
+
+    fn default_routes(&self) -> Result<Vec<String>, NetworkError> {
+        Ok(self.default_routes.clone())
+    }
+}
+
+/// Internal builder for the networks of the tests, so they only spell
+/// out the fields they are about, like `network("eth0").index(2)`.
+#[cfg(test)]
+pub(crate) struct FixtureNetwork(Network);
+
+/// Internal method to start a network of the tests with only a name.
+#[cfg(test)]
+pub(crate) fn network(name: &str) -> FixtureNetwork {
+    FixtureNetwork(Network {
+        name: name.to_string(),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+impl FixtureNetwork {
+    pub(crate) fn index(mut self, index: u32) -> FixtureNetwork {
+        self.0.index = Some(index);
+        self
+    }
+
+    pub(crate) fn inet(mut self, inet: &str) -> FixtureNetwork {
+        self.0.inet = Some(inet.to_string());
+        self
+    }
+
+    pub(crate) fn mac(mut self, mac: &str) -> FixtureNetwork {
+        self.0.mac = Some(mac.to_string());
+        self
+    }
+
+    pub(crate) fn mtu(mut self, mtu: u32) -> FixtureNetwork {
+        self.0.mtu = Some(mtu);
+        self
+    }
+
+    pub(crate) fn flags(mut self, flags: InterfaceFlags) -> FixtureNetwork {
+        self.0.flags = flags;
+        self
+    }
+
+    pub(crate) fn link_kind(mut self, kind: InterfaceKind) -> FixtureNetwork {
+        self.0.link_kind = Some(kind);
+        self
+    }
+}
+
+#[cfg(test)]
+impl FixtureBackend {
+    /// Creates a backend from the networks of the tests.
+    pub(crate) fn of<const N: usize>(networks: [FixtureNetwork; N]) -> FixtureBackend {
+        FixtureBackend::new(networks.into_iter().map(|x| x.0).collect())
+    }
+}
@@ -0,0 +1,356 @@
+//! # Getifaddrs
+//! Reads the network interfaces with `getifaddrs(3)` from the C
+//! library, the same way most C tools do. It is a single call that
+//! does not spawn a process, and is available on every Unix.
+//! The hardware address is only read on Linux, BSD and macOS.
+
+use std::ffi::CStr;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::backend::Backend;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::backend::{host_default_routes, link_type_name};
+use crate::{Inet6Address, InetAddress, InterfaceFlags, Netmask, Network, NetworkError};
+
+/// # Getifaddrs Backend
+/// A backend that walks the list returned by `getifaddrs(3)`.
+/// The list has an entry for every address of every interface:
+/// * `AF_INET`: An IPv4 address with its netmask, and the broadcast
+///   or peer address.
+/// * `AF_INET6`: An IPv6 address with its netmask.
+/// * `AF_PACKET` on Linux and `AF_LINK` on BSD and macOS: The hardware
+///   address and index of the interface. On Linux it also has the
+///   traffic counters, which are only 32 bits and wrap around.
+///
+/// Like `ifconfig`, only the interfaces that are up are returned,
+/// unless `all_networks` is used.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GetifaddrsBackend;
+
+impl Backend for GetifaddrsBackend {
+    fn name(&self) -> &str {
+        "getifaddrs"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn networks(&self) -> Result<Vec<Network>, NetworkError> {
+        Ok(get_networks()?
+            .into_iter()
+            .filter(|network| network.is_up())
+            .collect())
+    }
+
+    fn all_networks(&self) -> Result<Vec<Network>, NetworkError> {
+        Ok(get_networks()?)
+    }
+
+    fn default_routes(&self) -> Result<Vec<String>, NetworkError> {
+        host_default_routes()
+    }
+}
+
+/// Owns the list returned by `getifaddrs`, and frees it when dropped.
+struct IfAddrs(*mut libc::ifaddrs);
+
+impl IfAddrs {
+    fn new() -> io::Result<IfAddrs> {
+        let mut list = std::ptr::null_mut();
+        if unsafe { libc::getifaddrs(&mut list) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(IfAddrs(list))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &libc::ifaddrs> {
+        let mut next = self.0;
+        std::iter::from_fn(move || {
+            let entry = unsafe { next.as_ref()? };
+            next = entry.ifa_next;
+            Some(entry)
+        })
+    }
+}
+
+impl Drop for IfAddrs {
+    fn drop(&mut self) {
+        unsafe {
+            libc::freeifaddrs(self.0);
+        }
+    }
+}
+
+/// The broadcast or point-to-point destination address of an entry,
+/// which share a field that is named differently on Linux.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn destination(entry: &libc::ifaddrs) -> *mut libc::sockaddr {
+    entry.ifa_ifu
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn destination(entry: &libc::ifaddrs) -> *mut libc::sockaddr {
+    entry.ifa_dstaddr
+}
+
+/// The family of a socket address, or None if there is no address.
+fn family(addr: *const libc::sockaddr) -> Option<libc::c_int> {
+    unsafe { addr.as_ref() }.map(|addr| addr.sa_family as libc::c_int)
+}
+
+fn ipv4(addr: *const libc::sockaddr) -> Option<Ipv4Addr> {
+    if family(addr)? != libc::AF_INET {
+        return None;
+    }
+
+    let addr = unsafe { &*(addr as *const libc::sockaddr_in) };
+    Some(Ipv4Addr::from(addr.sin_addr.s_addr.to_ne_bytes()))
+}
+
+fn ipv6(addr: *const libc::sockaddr) -> Option<Ipv6Addr> {
+    if family(addr)? != libc::AF_INET6 {
+        return None;
+    }
+
+    let addr = unsafe { &*(addr as *const libc::sockaddr_in6) };
+    Some(Ipv6Addr::from(addr.sin6_addr.s6_addr))
+}
+
+/// The flags of an entry. Linux uses the same values as
+/// `InterfaceFlags`, other systems have to be mapped by name.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn flags(raw: u32) -> InterfaceFlags {
+    InterfaceFlags::from_bits(raw)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn flags(raw: u32) -> InterfaceFlags {
+    let known = [
+        (libc::IFF_UP, InterfaceFlags::UP),
+        (libc::IFF_BROADCAST, InterfaceFlags::BROADCAST),
+        (libc::IFF_DEBUG, InterfaceFlags::DEBUG),
+        (libc::IFF_LOOPBACK, InterfaceFlags::LOOPBACK),
+        (libc::IFF_POINTOPOINT, InterfaceFlags::POINTOPOINT),
+        (libc::IFF_RUNNING, InterfaceFlags::RUNNING),
+        (libc::IFF_NOARP, InterfaceFlags::NOARP),
+        (libc::IFF_PROMISC, InterfaceFlags::PROMISC),
+        (libc::IFF_ALLMULTI, InterfaceFlags::ALLMULTI),
+        (libc::IFF_MULTICAST, InterfaceFlags::MULTICAST),
+    ];
+
+    let mut flags = InterfaceFlags::default();
+    for (bit, flag) in known {
+        if raw & bit as u32 != 0 {
+            flags.insert(flag);
+        }
+    }
+
+    flags
+}
+
+/// Reads the `AF_PACKET` entry of an interface, which has its index,
+/// hardware address and the counters of `struct rtnl_link_stats`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn parse_link(network: &mut Network, entry: &libc::ifaddrs) {
+    let addr = unsafe { &*(entry.ifa_addr as *const libc::sockaddr_ll) };
+
+    network.index = Some(addr.sll_ifindex as u32);
+    network.link_type = Some(link_type_name(addr.sll_hatype));
+    if addr.sll_hatype == libc::ARPHRD_ETHER && addr.sll_halen == 6 {
+        network.mac = Some(
+            crate::MacAddr::from([
+                addr.sll_addr[0],
+                addr.sll_addr[1],
+                addr.sll_addr[2],
+                addr.sll_addr[3],
+                addr.sll_addr[4],
+                addr.sll_addr[5],
+            ])
+            .to_string(),
+        );
+    }
+
+    if let Some(stats) = unsafe { (entry.ifa_data as *const [u32; 23]).as_ref() } {
+        let counter = |index: usize| stats[index] as u64;
+
+        network.stats = Some(crate::InterfaceStats {
+            rx_packets: counter(0),
+            tx_packets: counter(1),
+            rx_bytes: counter(2),
+            tx_bytes: counter(3),
+            rx_errors: counter(4),
+            tx_errors: counter(5),
+            rx_dropped: counter(6),
+            tx_dropped: counter(7),
+            multicast: counter(8),
+            tx_collisions: counter(9),
+            rx_frame: counter(13),
+            rx_overruns: counter(14),
+            rx_missed: counter(15),
+            tx_carrier: counter(17),
+            tx_overruns: counter(18),
+            rx_compressed: counter(21),
+            tx_compressed: counter(22),
+        });
+    }
+}
+
+/// Reads the `AF_LINK` entry of an interface, which has its index and
+/// hardware address after the name in `sdl_data`.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+))]
+fn parse_link(network: &mut Network, entry: &libc::ifaddrs) {
+    const IFT_ETHER: u8 = 0x06;
+
+    let addr = unsafe { &*(entry.ifa_addr as *const libc::sockaddr_dl) };
+
+    network.index = Some(addr.sdl_index as u32);
+    if addr.sdl_type == IFT_ETHER && addr.sdl_alen == 6 {
+        let data = addr.sdl_data.as_ptr() as *const u8;
+        let mut octets = [0u8; 6];
+        for (i, octet) in octets.iter_mut().enumerate() {
+            *octet = unsafe { *data.add(addr.sdl_nlen as usize + i) };
+        }
+        network.mac = Some(crate::MacAddr::from(octets).to_string());
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const AF_LINK: libc::c_int = libc::AF_PACKET;
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+))]
+const AF_LINK: libc::c_int = libc::AF_LINK;
+
+/// Other systems do not get the hardware address.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+)))]
+const AF_LINK: libc::c_int = -1;
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+)))]
+fn parse_link(_network: &mut Network, _entry: &libc::ifaddrs) {}
+
+fn parse_inet(network: &mut Network, entry: &libc::ifaddrs) {
+    let address = match ipv4(entry.ifa_addr) {
+        Some(address) => address,
+        None => return,
+    };
+    let prefix_len = ipv4(entry.ifa_netmask)
+        .and_then(|x| Netmask::try_from(x).ok())
+        .map(|x| x.prefix_len())
+        .unwrap_or(32);
+
+    let mut inet = InetAddress::new(address, prefix_len);
+    if network.is_point_to_point() {
+        inet.peer = ipv4(destination(entry));
+    } else if network.flags.contains(InterfaceFlags::BROADCAST) {
+        inet.broadcast = ipv4(destination(entry));
+    }
+
+    network.push_inet(inet);
+}
+
+fn parse_inet6(network: &mut Network, entry: &libc::ifaddrs) {
+    let mut address = match ipv6(entry.ifa_addr) {
+        Some(address) => address,
+        None => return,
+    };
+    let prefix_len = ipv6(entry.ifa_netmask)
+        .map(|x| u128::from(x).leading_ones() as u8)
+        .unwrap_or(128);
+
+    // BSD keeps the index of the interface in the second group
+    // of link-local addresses, like `fe80:4::1`.
+    let mut segments = address.segments();
+    if segments[0] & 0xffc0 == 0xfe80 && segments[1] != 0 {
+        segments[1] = 0;
+        address = Ipv6Addr::from(segments);
+    }
+
+    network
+        .inet6_addresses
+        .push(Inet6Address::new(address, prefix_len));
+}
+
+/// Lists every network interface with all of its addresses,
+/// in the order `getifaddrs` first mentions them.
+fn get_networks() -> io::Result<Vec<Network>> {
+    let list = IfAddrs::new()?;
+    let mut networks: Vec<Network> = Vec::new();
+
+    for entry in list.iter() {
+        let name = unsafe { CStr::from_ptr(entry.ifa_name) }
+            .to_string_lossy()
+            .to_string();
+
+        let position = match networks.iter().position(|x| x.name == name) {
+            Some(position) => position,
+            None => {
+                networks.push(Network {
+                    name,
+                    flags: flags(entry.ifa_flags),
+                    raw_flags: Some(entry.ifa_flags),
+                    ..Default::default()
+                });
+                networks.len() - 1
+            }
+        };
+        let network = &mut networks[position];
+
+        match family(entry.ifa_addr) {
+            Some(libc::AF_INET) => parse_inet(network, entry),
+            Some(libc::AF_INET6) => parse_inet6(network, entry),
+            Some(AF_LINK) => parse_link(network, entry),
+            _ => {}
+        }
+    }
+
+    Ok(networks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_getifaddrs_work() {
+        let networks = GetifaddrsBackend.networks().unwrap();
+        let lo = networks.iter().find(|x| x.is_loopback()).unwrap();
+
+        assert!(lo.inet_addr().unwrap().is_loopback());
+        assert_eq!(lo.inet_addresses[0].prefix_len, 8);
+        assert!(lo.index.is_some());
+    }
+}
@@ -0,0 +1,72 @@
+//! # Ifconfig
+//! Runs the `ifconfig` command and parses its output with
+//! `parse_network`. This works on any unix system with net-tools
+//! installed, and is the fallback when nothing better is available.
+
+use crate::backend::{command_exists, host_default_routes, run_command, Backend};
+use crate::parser::split_interfaces;
+use crate::{parse_network, Network, NetworkError};
+
+/// Internal method to get the output of `ifconfig` split into
+/// a vector of strings for each network interface.
+/// * `args`: The arguments, like `-a` to include the interfaces
+///   that are down.
+///
+/// # Errors
+///
+/// Returns a `NetworkError` if `ifconfig` fails to execute.
+fn get_ifconfig_text(args: &[&str]) -> Result<Vec<String>, NetworkError> {
+    let ifconfig_text = run_command("ifconfig", args)?;
+
+    Ok(split_interfaces(&ifconfig_text)
+        .into_iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>())
+}
+
+/// # Ifconfig Backend
+/// A backend that runs `ifconfig` and parses its output.
+/// `all_networks` runs `ifconfig -a` instead.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IfconfigBackend;
+
+impl Backend for IfconfigBackend {
+    fn name(&self) -> &str {
+        "ifconfig"
+    }
+
+    fn is_available(&self) -> bool {
+        command_exists("ifconfig")
+    }
+
+    fn networks(&self) -> Result<Vec<Network>, NetworkError> {
+        parse_ifconfig_text(get_ifconfig_text(&[])?)
+    }
+
+    fn all_networks(&self) -> Result<Vec<Network>, NetworkError> {
+        parse_ifconfig_text(get_ifconfig_text(&["-a"])?)
+    }
+
+    fn default_routes(&self) -> Result<Vec<String>, NetworkError> {
+        host_default_routes()
+    }
+}
+
+/// Internal method to parse the text of every interface, failing
+/// on text that does not even have a name.
+fn parse_ifconfig_text(texts: Vec<String>) -> Result<Vec<Network>, NetworkError> {
+    let mut networks = Vec::new();
+
+    for text in texts.iter().filter(|x| !x.trim().is_empty()) {
+        let network = parse_network(text);
+        if network.name.is_empty() {
+            return Err(NetworkError::Parse(format!(
+                "no interface name in {:?}",
+                text
+            )));
+        }
+        networks.push(network);
+    }
+
+    Ok(networks)
+}
@@ -0,0 +1,59 @@
+//! # Ip
+//! Runs iproute2's `ip` command with JSON output and parses it with
+//! `parse_ip_json`. iproute2 is installed on most Linux systems that
+//! do not have net-tools, and its JSON output does not need guessing.
+
+use crate::backend::{command_exists, host_default_routes, run_command, Backend};
+use crate::{parse_ip_json, Network, NetworkError};
+
+/// # Ip Backend
+/// A backend that runs `ip -json -details -stats addr show` and
+/// parses its output. Like `ifconfig`, only the interfaces that
+/// are up are returned.
+///
+/// This needs iproute2 4.14 or newer, which added JSON output.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IpBackend;
+
+impl Backend for IpBackend {
+    fn name(&self) -> &str {
+        "ip"
+    }
+
+    fn is_available(&self) -> bool {
+        command_exists("ip")
+    }
+
+    fn networks(&self) -> Result<Vec<Network>, NetworkError> {
+        Ok(self
+            .all_networks()?
+            .into_iter()
+            .filter(|network| network.is_up())
+            .collect())
+    }
+
+    fn all_networks(&self) -> Result<Vec<Network>, NetworkError> {
+        let text = run_command("ip", &["-json", "-details", "-stats", "addr", "show"])?;
+
+        parse_ip_json(&text)
+    }
+
+    fn default_routes(&self) -> Result<Vec<String>, NetworkError> {
+        host_default_routes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_ip_backend_work() {
+        if !IpBackend.is_available() {
+            return;
+        }
+
+        let networks = IpBackend.networks().unwrap();
+        assert!(networks.iter().any(|network| network.is_loopback()));
+    }
+}
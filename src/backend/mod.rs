@@ -0,0 +1,327 @@
+//! # Backend
+//! The sources the networks can be read from.
+//! Every source implements the `Backend` trait, so the methods of
+//! this crate can be driven by any of them, including a fake one.
+
+use crate::{Network, NetworkError};
+
+pub(crate) mod fixture;
+#[cfg(unix)]
+mod getifaddrs;
+mod ifconfig;
+mod ip;
+#[cfg(target_os = "linux")]
+mod netlink;
+mod root;
+mod sysfs;
+
+pub use fixture::FixtureBackend;
+#[cfg(unix)]
+pub use getifaddrs::GetifaddrsBackend;
+pub use ifconfig::IfconfigBackend;
+pub use ip::IpBackend;
+#[cfg(target_os = "linux")]
+pub use netlink::NetlinkBackend;
+pub use root::NetworkSource;
+pub use sysfs::SysfsBackend;
+
+/// # Backend
+/// A source of network interfaces, like the `ifconfig` command
+/// or the kernel's netlink interface.
+///
+/// Implement this trait to feed your own networks to methods like
+/// `get_networks_with` and `find_network_with`. Every method of the
+/// crate that ends in `_with` is the one without it, but it reads the
+/// networks from the given backend instead of detecting one. Other
+/// lookups can be written as a `NetworkQuery` and run with `run_with`.
+pub trait Backend {
+    /// A short name for the backend, like `ifconfig` or `netlink`.
+    fn name(&self) -> &str;
+
+    /// Whether the backend can be used on this system.
+    /// This is meant to be cheap, so it does not guarantee that
+    /// reading the networks will succeed.
+    fn is_available(&self) -> bool;
+
+    /// Reads the network interfaces from the backend.
+    /// Like plain `ifconfig`, only the interfaces that are up are
+    /// returned.
+    fn networks(&self) -> Result<Vec<Network>, NetworkError>;
+
+    /// Reads all of the network interfaces from the backend, including
+    /// the ones that are down or have no address, like `ifconfig -a`.
+    ///
+    /// Backends that can not see the interfaces that are down return
+    /// the same networks as `networks`, which is what this does
+    /// unless it is implemented.
+    fn all_networks(&self) -> Result<Vec<Network>, NetworkError> {
+        self.networks()
+    }
+
+    /// Lists the names of the interfaces that have a default route,
+    /// for IPv4 or IPv6.
+    ///
+    /// Unless it is implemented, no interface is listed, so the routes
+    /// of this host are never mixed with networks read from elsewhere.
+    fn default_routes(&self) -> Result<Vec<String>, NetworkError> {
+        Ok(Vec::new())
+    }
+}
+
+impl<B: Backend + ?Sized> Backend for &B {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn is_available(&self) -> bool {
+        (**self).is_available()
+    }
+
+    fn networks(&self) -> Result<Vec<Network>, NetworkError> {
+        (**self).networks()
+    }
+
+    fn all_networks(&self) -> Result<Vec<Network>, NetworkError> {
+        (**self).all_networks()
+    }
+
+    fn default_routes(&self) -> Result<Vec<String>, NetworkError> {
+        (**self).default_routes()
+    }
+}
+
+impl<B: Backend + ?Sized> Backend for Box<B> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn is_available(&self) -> bool {
+        (**self).is_available()
+    }
+
+    fn networks(&self) -> Result<Vec<Network>, NetworkError> {
+        (**self).networks()
+    }
+
+    fn all_networks(&self) -> Result<Vec<Network>, NetworkError> {
+        (**self).all_networks()
+    }
+
+    fn default_routes(&self) -> Result<Vec<String>, NetworkError> {
+        (**self).default_routes()
+    }
+}
+
+/// Internal method to get the default routes of this host for the
+/// backends that read its live interfaces. They come from the routing
+/// tables in `/proc/net` on Linux. Other systems have no such files,
+/// so no interface is listed.
+pub(crate) fn host_default_routes() -> Result<Vec<String>, NetworkError> {
+    match cfg!(target_os = "linux") {
+        true => SysfsBackend::new().default_routes(),
+        false => Ok(Vec::new()),
+    }
+}
+
+/// Internal method to run a command and get its standard output.
+///
+/// # Errors
+///
+/// Returns a `NetworkError` if the command is not installed, can
+/// not be executed, exits with a non-zero status or prints
+/// something that is not UTF-8.
+pub(crate) fn run_command(command: &str, args: &[&str]) -> Result<String, NetworkError> {
+    let output = std::process::Command::new(command)
+        .args(args)
+        .output()
+        .map_err(|error| match error.kind() {
+            std::io::ErrorKind::NotFound => NetworkError::CommandNotFound {
+                command: command.to_string(),
+            },
+            std::io::ErrorKind::PermissionDenied => {
+                NetworkError::PermissionDenied(command.to_string())
+            }
+            _ => NetworkError::Io(error),
+        })?;
+
+    if !output.status.success() {
+        return Err(NetworkError::CommandFailed {
+            command: command.to_string(),
+            code: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    String::from_utf8(output.stdout).map_err(|_| NetworkError::InvalidUtf8 {
+        command: command.to_string(),
+    })
+}
+
+/// Internal method to check if a command can be found in `PATH`,
+/// without running it.
+pub(crate) fn command_exists(command: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| dir.join(command).is_file()))
+        .unwrap_or(false)
+}
+
+/// Gets the name `ip` prints after `link/` for an `ARPHRD_*` type.
+pub(crate) fn link_type_name(link_type: u16) -> String {
+    let name = match link_type {
+        1 => "ether",
+        32 => "infiniband",
+        280 => "can",
+        512 => "ppp",
+        519 => "rawip",
+        768 => "ipip",
+        769 => "tunnel6",
+        772 => "loopback",
+        776 => "sit",
+        778 => "gre",
+        801 => "ieee802.11",
+        803 => "ieee802.11/radiotap",
+        823 => "gre6",
+        65534 => "none",
+        65535 => "void",
+        other => return format!("[{}]", other),
+    };
+
+    name.to_string()
+}
+
+/// # Available Backends
+///
+/// Lists the backends that can be used on this system,
+/// from the most to the least preferred.
+///
+/// # Returns
+///
+/// `Vec<Box<dyn Backend>>`: The available backends.
+pub fn available_backends() -> Vec<Box<dyn Backend>> {
+    let backends: Vec<Box<dyn Backend>> = vec![
+        #[cfg(target_os = "linux")]
+        Box::new(NetlinkBackend),
+        #[cfg(unix)]
+        Box::new(GetifaddrsBackend),
+        Box::new(IpBackend),
+        Box::new(IfconfigBackend),
+        #[cfg(target_os = "linux")]
+        Box::new(SysfsBackend::new()),
+    ];
+
+    backends
+        .into_iter()
+        .filter(|backend| backend.is_available())
+        .collect()
+}
+
+/// # Detect Backend
+///
+/// Picks the best backend available on this system.
+/// On Linux this is netlink, then `getifaddrs`, then the `ip`
+/// command and otherwise `ifconfig`.
+/// The files in `/sys` and `/proc` are the last resort, for when no
+/// socket can be opened and no process can be spawned.
+/// If nothing is available, the `ifconfig` backend is returned
+/// anyway, so the error explains what is missing.
+///
+/// # Returns
+///
+/// `Box<dyn Backend>`: The detected backend.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::{detect_backend, get_networks_with};
+///
+/// let backend = detect_backend();
+/// println!("Using {}", backend.name());
+///
+/// for network in get_networks_with(&backend) {
+///     println!("{}", network);
+/// }
+/// ```
+pub fn detect_backend() -> Box<dyn Backend> {
+    available_backends()
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| Box::new(IfconfigBackend))
+}
+
+/// Reads the networks from the first available backend that
+/// succeeds, returning the last error if none of them do.
+pub(crate) fn auto_networks() -> Result<Vec<Network>, NetworkError> {
+    auto(|backend| backend.networks())
+}
+
+/// Like `auto_networks`, but includes the interfaces that are down.
+pub(crate) fn auto_all_networks() -> Result<Vec<Network>, NetworkError> {
+    auto(|backend| backend.all_networks())
+}
+
+/// Runs `read` against the first available backend that succeeds,
+/// returning the last error if none of them do.
+pub(crate) fn auto<T>(
+    read: impl Fn(&dyn Backend) -> Result<T, NetworkError>,
+) -> Result<T, NetworkError> {
+    let mut last_error = None;
+
+    for backend in available_backends() {
+        match read(backend.as_ref()) {
+            Ok(networks) => return Ok(networks),
+            Err(error) => last_error = Some(error),
+        }
+    }
+
+    Err(last_error.unwrap_or(NetworkError::CommandNotFound {
+        command: "ifconfig".to_string(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_run_command_report_errors() {
+        assert!(matches!(
+            run_command("ip-extractor-missing-command", &[]),
+            Err(NetworkError::CommandNotFound { .. })
+        ));
+        assert!(matches!(
+            run_command("sh", &["-c", "echo oops >&2; exit 3"]),
+            Err(NetworkError::CommandFailed { code: Some(3), ref stderr, .. }) if stderr == "oops\n"
+        ));
+        assert!(matches!(
+            run_command("printf", &["\\377"]),
+            Err(NetworkError::InvalidUtf8 { .. })
+        ));
+    }
+
+    #[test]
+    fn does_command_exists_work() {
+        assert!(command_exists("sh"));
+        assert!(!command_exists("ip-extractor-missing-command"));
+    }
+
+    #[test]
+    fn does_detect_backend_work() {
+        assert!(detect_backend().is_available());
+    }
+
+    #[test]
+    fn does_fixture_backend_work() {
+        let backend = FixtureBackend::from_ifconfig(
+            "eth0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500
+        inet 10.0.0.5  netmask 255.255.255.0  broadcast 10.0.0.255
+
+lo: flags=73<UP,LOOPBACK,RUNNING>  mtu 65536
+        inet 127.0.0.1  netmask 255.0.0.0
+",
+        );
+        let networks = backend.networks().unwrap();
+        assert_eq!(networks.len(), 2);
+        assert_eq!(networks[0].inet.as_deref(), Some("10.0.0.5"));
+        assert_eq!(networks[1].name, "lo");
+    }
+}
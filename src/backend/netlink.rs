@@ -0,0 +1,596 @@
+//! # Netlink
+//! Reads the network interfaces straight from the kernel over a
+//! `NETLINK_ROUTE` socket, using the same `RTM_GETLINK` and
+//! `RTM_GETADDR` dumps that `ip` uses.
+//! This avoids spawning `ifconfig` and works on systems that do not
+//! ship net-tools at all, like distroless containers.
+
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::os::unix::io::RawFd;
+
+use crate::backend::{host_default_routes, link_type_name, Backend};
+use crate::{
+    AddressFlags, Inet6Address, InetAddress, InterfaceFlags, InterfaceKind, InterfaceStats,
+    Lifetime, Network, NetworkError, OperState, Scope,
+};
+
+const NLMSG_HDRLEN: usize = 16;
+const IFINFOMSG_LEN: usize = 16;
+const IFADDRMSG_LEN: usize = 8;
+const RTA_HDRLEN: usize = 4;
+
+const IFLA_ADDRESS: u16 = 1;
+const IFLA_IFNAME: u16 = 3;
+const IFLA_MTU: u16 = 4;
+const IFLA_LINK: u16 = 5;
+const IFLA_MASTER: u16 = 10;
+const IFLA_OPERSTATE: u16 = 16;
+const IFLA_LINKINFO: u16 = 18;
+const IFLA_STATS64: u16 = 23;
+const IFLA_LINK_NETNSID: u16 = 37;
+const IFLA_PROP_LIST: u16 = 52;
+const IFLA_ALT_IFNAME: u16 = 53;
+
+const IFLA_INFO_KIND: u16 = 1;
+const IFLA_INFO_DATA: u16 = 2;
+const IFLA_TUN_TYPE: u16 = 3;
+const IFF_TAP: u8 = 2;
+
+const IFA_ADDRESS: u16 = 1;
+const IFA_LOCAL: u16 = 2;
+const IFA_LABEL: u16 = 3;
+const IFA_BROADCAST: u16 = 4;
+const IFA_CACHEINFO: u16 = 6;
+const IFA_FLAGS: u16 = 8;
+
+const IFA_F_SECONDARY: u32 = 0x01;
+
+const ARPHRD_ETHER: u16 = 1;
+
+const RECV_BUFFER_LEN: usize = 32 * 1024;
+
+/// Rounds `len` up to the 4 byte alignment netlink uses for
+/// both messages and attributes.
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_ne_bytes([buf[offset], buf[offset + 1]])
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes([
+        buf[offset],
+        buf[offset + 1],
+        buf[offset + 2],
+        buf[offset + 3],
+    ])
+}
+
+/// Splits a buffer of route attributes into `(type, payload)` pairs.
+fn attributes(mut buf: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut attributes = Vec::new();
+
+    while buf.len() >= RTA_HDRLEN {
+        let len = read_u16(buf, 0) as usize;
+        if len < RTA_HDRLEN || len > buf.len() {
+            break;
+        }
+
+        // The upper bits are the NLA_F_NESTED and NLA_F_NET_BYTEORDER flags.
+        let kind = read_u16(buf, 2) & 0x3fff;
+        attributes.push((kind, &buf[RTA_HDRLEN..len]));
+
+        buf = &buf[align(len).min(buf.len())..];
+    }
+
+    attributes
+}
+
+/// Formats a hardware address the way `ifconfig` prints it.
+fn format_mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<String>>()
+        .join(":")
+}
+
+fn ipv4(bytes: &[u8]) -> Option<Ipv4Addr> {
+    let octets: [u8; 4] = bytes.get(..4)?.try_into().ok()?;
+    Some(Ipv4Addr::from(octets))
+}
+
+fn ipv6(bytes: &[u8]) -> Option<Ipv6Addr> {
+    let octets: [u8; 16] = bytes.get(..16)?.try_into().ok()?;
+    Some(Ipv6Addr::from(octets))
+}
+
+/// A `NETLINK_ROUTE` socket that is closed when dropped.
+struct Socket {
+    fd: RawFd,
+    seq: u32,
+}
+
+impl Socket {
+    fn open() -> io::Result<Socket> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::NETLINK_ROUTE,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Socket { fd, seq: 0 })
+    }
+
+    /// Sends a dump request of type `kind` with the given header
+    /// payload and collects the bodies of every reply message.
+    fn dump(&mut self, kind: u16, payload: &[u8]) -> io::Result<Vec<Vec<u8>>> {
+        self.seq += 1;
+
+        let len = NLMSG_HDRLEN + payload.len();
+        let mut request = Vec::with_capacity(align(len));
+        request.extend_from_slice(&(len as u32).to_ne_bytes());
+        request.extend_from_slice(&kind.to_ne_bytes());
+        request.extend_from_slice(&((libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16).to_ne_bytes());
+        request.extend_from_slice(&self.seq.to_ne_bytes());
+        request.extend_from_slice(&0u32.to_ne_bytes());
+        request.extend_from_slice(payload);
+        request.resize(align(len), 0);
+
+        let sent = unsafe {
+            libc::send(
+                self.fd,
+                request.as_ptr() as *const libc::c_void,
+                request.len(),
+                0,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut messages = Vec::new();
+        let mut buf = vec![0u8; RECV_BUFFER_LEN];
+
+        loop {
+            // A message that does not fit is cut short by the kernel,
+            // so its real size is peeked first and the buffer grown.
+            let size = self.recv(&mut buf, libc::MSG_PEEK | libc::MSG_TRUNC)?;
+            if size > buf.len() {
+                buf.resize(size, 0);
+            }
+            let received = self.recv(&mut buf, libc::MSG_TRUNC)?;
+            if received > buf.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "truncated netlink message",
+                ));
+            }
+
+            let mut rest = &buf[..received];
+            while rest.len() >= NLMSG_HDRLEN {
+                let msg_len = read_u32(rest, 0) as usize;
+                if msg_len < NLMSG_HDRLEN || msg_len > rest.len() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "truncated netlink message",
+                    ));
+                }
+
+                let msg_type = read_u16(rest, 4);
+                let msg_seq = read_u32(rest, 8);
+                let body = &rest[NLMSG_HDRLEN..msg_len];
+                rest = &rest[align(msg_len).min(rest.len())..];
+
+                if msg_seq != self.seq {
+                    continue;
+                }
+
+                match msg_type as libc::c_int {
+                    libc::NLMSG_DONE => return Ok(messages),
+                    libc::NLMSG_ERROR => {
+                        let code = match body.get(..4) {
+                            Some(code) => i32::from_ne_bytes([code[0], code[1], code[2], code[3]]),
+                            None => {
+                                return Err(io::Error::new(
+                                    io::ErrorKind::InvalidData,
+                                    "truncated netlink error message",
+                                ))
+                            }
+                        };
+                        if code != 0 {
+                            return Err(io::Error::from_raw_os_error(-code));
+                        }
+                    }
+                    _ => messages.push(body.to_vec()),
+                }
+            }
+        }
+    }
+
+    /// Receives a message into the buffer, retrying when interrupted.
+    /// With `MSG_TRUNC` the real size of the message is returned, even
+    /// if it is larger than the buffer.
+    fn recv(&self, buf: &mut [u8], flags: libc::c_int) -> io::Result<usize> {
+        loop {
+            let received = unsafe {
+                libc::recv(
+                    self.fd,
+                    buf.as_mut_ptr() as *mut libc::c_void,
+                    buf.len(),
+                    flags,
+                )
+            };
+            if received >= 0 {
+                return Ok(received as usize);
+            }
+
+            let error = io::Error::last_os_error();
+            if error.kind() != io::ErrorKind::Interrupted {
+                return Err(error);
+            }
+        }
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+/// Reads a NUL terminated string attribute.
+fn string(payload: &[u8]) -> String {
+    let bytes = payload.split(|byte| *byte == 0).next().unwrap_or_default();
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+/// Reads the counters of an `IFLA_STATS64` attribute, which is a
+/// `struct rtnl_link_stats64` of native endian `u64`s.
+fn parse_stats64(payload: &[u8]) -> Option<InterfaceStats> {
+    let counters = payload
+        .chunks_exact(8)
+        .map(|x| u64::from_ne_bytes(x.try_into().unwrap()))
+        .collect::<Vec<u64>>();
+    if counters.len() < 23 {
+        return None;
+    }
+
+    Some(InterfaceStats {
+        rx_packets: counters[0],
+        tx_packets: counters[1],
+        rx_bytes: counters[2],
+        tx_bytes: counters[3],
+        rx_errors: counters[4],
+        tx_errors: counters[5],
+        rx_dropped: counters[6],
+        tx_dropped: counters[7],
+        multicast: counters[8],
+        tx_collisions: counters[9],
+        rx_overruns: counters[14],
+        rx_frame: counters[13],
+        rx_missed: counters[15],
+        tx_carrier: counters[17],
+        tx_overruns: counters[18],
+        rx_compressed: counters[21],
+        tx_compressed: counters[22],
+    })
+}
+
+/// Reads the preferred and valid lifetimes of an `IFA_CACHEINFO` attribute.
+fn parse_cacheinfo(payload: &[u8]) -> Option<(Lifetime, Lifetime)> {
+    if payload.len() < 8 {
+        return None;
+    }
+
+    Some((
+        Lifetime::from_secs(read_u32(payload, 0)),
+        Lifetime::from_secs(read_u32(payload, 4)),
+    ))
+}
+
+/// Adds the IPv4 address of an `RTM_NEWADDR` message to the network.
+fn parse_inet(network: &mut Network, message: &[u8]) {
+    let prefix_len = message[1];
+    let mut flags = message[2] as u32;
+    let scope = Scope::from_rt_scope(message[3]);
+
+    let mut address = None;
+    let mut local = None;
+    let mut broadcast = None;
+    let mut label = None;
+    let mut lifetimes = None;
+
+    for (kind, payload) in attributes(&message[IFADDRMSG_LEN..]) {
+        match kind {
+            IFA_ADDRESS => address = ipv4(payload),
+            IFA_LOCAL => local = ipv4(payload),
+            IFA_BROADCAST => broadcast = ipv4(payload),
+            IFA_LABEL => label = Some(string(payload)),
+            IFA_CACHEINFO => lifetimes = parse_cacheinfo(payload),
+            IFA_FLAGS if payload.len() >= 4 => flags = read_u32(payload, 0),
+            _ => {}
+        }
+    }
+
+    // On point-to-point links IFA_ADDRESS is the peer, so prefer IFA_LOCAL.
+    if let Some(local) = local.or(address) {
+        network.push_inet(InetAddress {
+            broadcast,
+            peer: address.filter(|x| *x != local),
+            scope,
+            // For IPv4 the bit of IFA_F_TEMPORARY means secondary instead.
+            secondary: flags & IFA_F_SECONDARY != 0,
+            flags: AddressFlags::from_bits(flags & !IFA_F_SECONDARY),
+            label,
+            preferred_lft: lifetimes.map(|(preferred, _)| preferred),
+            valid_lft: lifetimes.map(|(_, valid)| valid),
+            ..InetAddress::new(local, prefix_len)
+        });
+    }
+}
+
+/// Adds the IPv6 address of an `RTM_NEWADDR` message to the network.
+fn parse_inet6(network: &mut Network, message: &[u8]) {
+    let prefix_len = message[1];
+    let mut flags = message[2] as u32;
+    let scope = Scope::from_rt_scope(message[3]);
+
+    let mut address = None;
+    let mut local = None;
+    let mut lifetimes = None;
+
+    for (kind, payload) in attributes(&message[IFADDRMSG_LEN..]) {
+        match kind {
+            IFA_ADDRESS => address = ipv6(payload),
+            IFA_LOCAL => local = ipv6(payload),
+            IFA_CACHEINFO => lifetimes = parse_cacheinfo(payload),
+            // The header only has room for the lower 8 bits of the flags.
+            IFA_FLAGS if payload.len() >= 4 => flags = read_u32(payload, 0),
+            _ => {}
+        }
+    }
+
+    if let Some(address) = local.or(address) {
+        network.inet6_addresses.push(Inet6Address {
+            scope,
+            flags: AddressFlags::from_bits(flags),
+            preferred_lft: lifetimes.map(|(preferred, _)| preferred),
+            valid_lft: lifetimes.map(|(_, valid)| valid),
+            ..Inet6Address::new(address, prefix_len)
+        });
+    }
+}
+
+/// Reads the kind of a link from the `IFLA_INFO_KIND` of its
+/// `IFLA_LINKINFO`, like `bridge`. A `tun` link is a tap if the
+/// `IFLA_TUN_TYPE` of its data says so.
+fn parse_linkinfo(payload: &[u8]) -> Option<InterfaceKind> {
+    let info = attributes(payload);
+    let kind = info
+        .iter()
+        .find(|(kind, _)| *kind == IFLA_INFO_KIND)
+        .map(|(_, payload)| string(payload))?;
+
+    let tap = info
+        .iter()
+        .filter(|(kind, _)| *kind == IFLA_INFO_DATA)
+        .flat_map(|(_, payload)| attributes(payload))
+        .any(|(kind, payload)| kind == IFLA_TUN_TYPE && payload.first() == Some(&IFF_TAP));
+
+    match kind.as_str() {
+        "tun" if tap => Some(InterfaceKind::Tap),
+        kind => InterfaceKind::from_link_kind(kind),
+    }
+}
+
+/// The kind of an Ethernet link without link info, which is a
+/// wireless card if it has a `wireless` or `phy80211` entry.
+fn physical_kind(name: &str) -> InterfaceKind {
+    let dir = std::path::Path::new("/sys/class/net").join(name);
+
+    if dir.join("wireless").exists() || dir.join("phy80211").exists() {
+        InterfaceKind::Wireless
+    } else {
+        InterfaceKind::Ethernet
+    }
+}
+
+/// Lists the network interfaces with all of their IPv4 and IPv6
+/// addresses. Unless `all` is set, only the ones that are up are
+/// listed, the same ones plain `ifconfig` shows.
+fn get_networks(all: bool) -> io::Result<Vec<Network>> {
+    let mut socket = Socket::open()?;
+
+    let mut links: Vec<(u32, Network)> = Vec::new();
+    let mut names: Vec<(u32, String)> = Vec::new();
+    let mut masters: Vec<(u32, u32)> = Vec::new();
+    let mut peers: Vec<(u32, u32)> = Vec::new();
+
+    let mut ifinfomsg = [0u8; IFINFOMSG_LEN];
+    ifinfomsg[0] = libc::AF_UNSPEC as u8;
+    for message in socket.dump(libc::RTM_GETLINK, &ifinfomsg)? {
+        if message.len() < IFINFOMSG_LEN {
+            continue;
+        }
+
+        let link_type = read_u16(&message, 2);
+        let index = read_u32(&message, 4);
+        let flags = read_u32(&message, 8);
+
+        let mut network = Network {
+            flags: InterfaceFlags::from_bits(flags),
+            raw_flags: Some(flags),
+            index: Some(index),
+            link_type: Some(link_type_name(link_type)),
+            ..Default::default()
+        };
+        let mut master = None;
+        let mut link = None;
+        let mut elsewhere = false;
+
+        for (kind, payload) in attributes(&message[IFINFOMSG_LEN..]) {
+            match kind {
+                IFLA_IFNAME => network.name = string(payload),
+                IFLA_ADDRESS if link_type == ARPHRD_ETHER => {
+                    network.mac = Some(format_mac(payload));
+                }
+                IFLA_MTU if payload.len() >= 4 => network.mtu = Some(read_u32(payload, 0)),
+                IFLA_OPERSTATE if !payload.is_empty() => {
+                    network.operstate = OperState::from_value(payload[0]);
+                }
+                IFLA_LINKINFO => network.link_kind = parse_linkinfo(payload),
+                IFLA_MASTER if payload.len() >= 4 => master = Some(read_u32(payload, 0)),
+                IFLA_LINK if payload.len() >= 4 => link = Some(read_u32(payload, 0)),
+                IFLA_LINK_NETNSID => elsewhere = true,
+                IFLA_STATS64 => network.stats = parse_stats64(payload),
+                IFLA_PROP_LIST => {
+                    network.altnames = attributes(payload)
+                        .into_iter()
+                        .filter(|(kind, _)| *kind == IFLA_ALT_IFNAME)
+                        .map(|(_, payload)| string(payload))
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+
+        // Physical links have no link info, so wireless cards are told
+        // apart from Ethernet ones by their entry in sysfs.
+        if network.link_kind.is_none() && link_type == ARPHRD_ETHER {
+            network.link_kind = Some(physical_kind(&network.name));
+        }
+
+        // A down link can still be the master of an up one, so its name is kept.
+        names.push((index, network.name.clone()));
+        if !all && flags & InterfaceFlags::UP.bits() == 0 {
+            continue;
+        }
+
+        if let Some(master) = master {
+            masters.push((index, master));
+        }
+        // A physical link is its own link, which `ip` does not print.
+        match link.filter(|link| *link != index) {
+            // The index is of a link in another namespace, like the peer of a veth.
+            Some(link) if elsewhere => network.set_peer(&format!("if{}", link)),
+            Some(link) => peers.push((index, link)),
+            None => {}
+        }
+        links.push((index, network));
+    }
+
+    // The master and peer are only known by their index until every link is read.
+    for (index, master) in masters {
+        let name = match names.iter().find(|(i, _)| *i == master) {
+            Some((_, name)) => name.clone(),
+            None => continue,
+        };
+        if let Some((_, network)) = links.iter_mut().find(|(i, _)| *i == index) {
+            network.master = Some(name);
+        }
+    }
+    for (index, peer) in peers {
+        let name = match names.iter().find(|(i, _)| *i == peer) {
+            Some((_, name)) => name.clone(),
+            None => continue,
+        };
+        if let Some((_, network)) = links.iter_mut().find(|(i, _)| *i == index) {
+            network.set_peer(&name);
+            // The other end of a veth is its peer, not its parent.
+            if network.link_kind == Some(InterfaceKind::Veth) {
+                network.parent = None;
+            }
+        }
+    }
+
+    let mut ifaddrmsg = [0u8; IFADDRMSG_LEN];
+    ifaddrmsg[0] = libc::AF_UNSPEC as u8;
+    for message in socket.dump(libc::RTM_GETADDR, &ifaddrmsg)? {
+        if message.len() < IFADDRMSG_LEN {
+            continue;
+        }
+
+        let index = read_u32(&message, 4);
+        let network = match links.iter_mut().find(|(i, _)| *i == index) {
+            Some((_, network)) => network,
+            None => continue,
+        };
+
+        match message[0] as libc::c_int {
+            libc::AF_INET => parse_inet(network, &message),
+            libc::AF_INET6 => parse_inet6(network, &message),
+            _ => {}
+        }
+    }
+
+    Ok(links.into_iter().map(|(_, network)| network).collect())
+}
+
+/// # Netlink Backend
+/// A backend that reads the network interfaces straight from the
+/// kernel over a `NETLINK_ROUTE` socket.
+/// This is the preferred backend on Linux, as it does not need
+/// any command to be installed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NetlinkBackend;
+
+impl Backend for NetlinkBackend {
+    fn name(&self) -> &str {
+        "netlink"
+    }
+
+    fn is_available(&self) -> bool {
+        Socket::open().is_ok()
+    }
+
+    fn networks(&self) -> Result<Vec<Network>, NetworkError> {
+        Ok(get_networks(false)?)
+    }
+
+    fn all_networks(&self) -> Result<Vec<Network>, NetworkError> {
+        Ok(get_networks(true)?)
+    }
+
+    fn default_routes(&self) -> Result<Vec<String>, NetworkError> {
+        host_default_routes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_attributes_work() {
+        // IFLA_IFNAME "lo\0" padded to 8 bytes, followed by IFLA_MTU.
+        let buf = [
+            7, 0, 3, 0, b'l', b'o', 0, 0, //
+            8, 0, 4, 0, 0, 0, 1, 0,
+        ];
+        let attributes = attributes(&buf);
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(attributes[0], (IFLA_IFNAME, &b"lo\0"[..]));
+        assert_eq!(attributes[1].0, 4);
+    }
+
+    #[test]
+    fn does_netlink_work() {
+        let networks = get_networks(false).unwrap();
+        assert!(networks.iter().any(|network| network.name == "lo"));
+
+        let all = get_networks(true).unwrap();
+        assert!(all.len() >= networks.len());
+        assert!(all.iter().all(|network| network.operstate.is_some()));
+    }
+}
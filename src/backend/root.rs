@@ -0,0 +1,253 @@
+//! # Root
+//! Reads the network interfaces of another machine from a copy of
+//! its files, like an unpacked sosreport or a support bundle, so
+//! they can be looked at the same way as the ones of this host.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::backend::{Backend, SysfsBackend};
+use crate::{parse_ip_text, parse_networks, parse_proc_net_dev, Network, NetworkError};
+
+/// The captures of `ip addr` a sosreport can have, newest first.
+const IP_ADDR_FILES: [&str; 3] = [
+    "sos_commands/networking/ip_-d_address",
+    "sos_commands/networking/ip_address",
+    "sos_commands/networking/ip_addr",
+];
+
+/// The capture of `ifconfig -a` in a sosreport.
+const IFCONFIG_FILE: &str = "sos_commands/networking/ifconfig_-a";
+
+/// # Network Source
+/// A backend that reads the networks from a captured system root,
+/// like an unpacked sosreport or a tarball of `/proc` and `/sys`.
+/// The first of these that is found is used:
+/// * `sos_commands/networking/ip_addr`: The output of `ip addr`,
+///   parsed with `parse_ip_text`. The `ip_address` and `ip_-d_address`
+///   names of newer sosreports are read too.
+/// * `sos_commands/networking/ifconfig_-a`: The output of `ifconfig -a`,
+///   parsed with `parse_networks`.
+/// * `sys/class/net` and `proc/net`: Read like the `SysfsBackend` does.
+///
+/// When a capture is used and the root has `sys/class/net` too, what
+/// the capture is missing, like the counters or the index, is filled
+/// in from it for the interfaces with the same name.
+/// If the capture has no counters, they are taken from `proc/net/dev`,
+/// and the default routes from the routing tables in `proc/net`.
+/// Like on the live host, only the interfaces that are up are returned,
+/// unless `all_networks` is used.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::{get_networks_with, NetworkSource};
+///
+/// let source = NetworkSource::from_root("tests/fixtures/sosreport-ip");
+///
+/// for network in get_networks_with(&source) {
+///     println!("{}", network);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct NetworkSource {
+    root: PathBuf,
+}
+
+impl NetworkSource {
+    /// Creates a source that reads the files under the given root.
+    pub fn from_root(root: impl Into<PathBuf>) -> NetworkSource {
+        NetworkSource { root: root.into() }
+    }
+
+    /// The root the files are read from.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Internal method to read the first capture that is found,
+    /// including the interfaces that are down.
+    fn read_networks(&self) -> Result<Vec<Network>, NetworkError> {
+        let sysfs = SysfsBackend::with_root(&self.root);
+
+        for file in IP_ADDR_FILES {
+            if let Some(text) = self.read(file)? {
+                return merge_sysfs(parse_ip_text(&text), &sysfs);
+            }
+        }
+
+        if let Some(text) = self.read(IFCONFIG_FILE)? {
+            return merge_sysfs(parse_networks(&text), &sysfs);
+        }
+
+        if sysfs.is_available() {
+            return sysfs.read_networks();
+        }
+
+        Err(NetworkError::Io(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no network information under {}", self.root.display()),
+        )))
+    }
+
+    /// Reads a file under the root, or None if it does not exist.
+    fn read(&self, file: &str) -> Result<Option<String>, NetworkError> {
+        match fs::read_to_string(self.root.join(file)) {
+            Ok(text) => Ok(Some(text)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+impl Backend for NetworkSource {
+    fn name(&self) -> &str {
+        "root"
+    }
+
+    fn is_available(&self) -> bool {
+        IP_ADDR_FILES
+            .iter()
+            .chain(&[IFCONFIG_FILE])
+            .any(|file| self.root.join(file).is_file())
+            || SysfsBackend::with_root(&self.root).is_available()
+    }
+
+    fn networks(&self) -> Result<Vec<Network>, NetworkError> {
+        Ok(self
+            .all_networks()?
+            .into_iter()
+            .filter(|network| network.is_up())
+            .collect())
+    }
+
+    fn all_networks(&self) -> Result<Vec<Network>, NetworkError> {
+        let mut networks = self.read_networks()?;
+
+        if let Some(text) = self.read("proc/net/dev")? {
+            let stats = parse_proc_net_dev(&text);
+            for network in networks.iter_mut().filter(|x| x.stats.is_none()) {
+                network.stats = stats
+                    .iter()
+                    .find(|(name, _)| *name == network.name)
+                    .map(|(_, stats)| *stats);
+            }
+        }
+
+        Ok(networks)
+    }
+
+    fn default_routes(&self) -> Result<Vec<String>, NetworkError> {
+        SysfsBackend::with_root(&self.root).default_routes()
+    }
+}
+
+/// Internal method to fill in the fields a capture does not have
+/// from the `sys/class/net` entry of the interface with the same
+/// name, if the root has one.
+fn merge_sysfs(
+    mut networks: Vec<Network>,
+    sysfs: &SysfsBackend,
+) -> Result<Vec<Network>, NetworkError> {
+    if !sysfs.is_available() {
+        return Ok(networks);
+    }
+
+    let links = sysfs.read_networks()?;
+    for network in networks.iter_mut() {
+        if let Some(link) = links.iter().find(|x| x.name == network.name) {
+            if network.flags.is_empty() {
+                network.flags = link.flags;
+            }
+            network.raw_flags = network.raw_flags.or(link.raw_flags);
+            network.mtu = network.mtu.or(link.mtu);
+            network.index = network.index.or(link.index);
+            network.stats = network.stats.or(link.stats);
+            network.mac = network.mac.take().or_else(|| link.mac.clone());
+            network.link_type = network.link_type.take().or_else(|| link.link_type.clone());
+            network.master = network.master.take().or_else(|| link.master.clone());
+            network.operstate = network.operstate.or(link.operstate);
+            network.link_kind = network.link_kind.or(link.link_kind);
+        }
+    }
+
+    Ok(networks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InterfaceKind, OperState};
+
+    #[test]
+    fn does_network_source_read_ip_addr() {
+        let source = NetworkSource::from_root("tests/fixtures/sosreport-ip");
+        assert!(source.is_available());
+
+        let networks = source.networks().unwrap();
+        let names = networks
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect::<Vec<&str>>();
+        // ip_addr is preferred over ifconfig_-a, which only has eth0.
+        assert_eq!(
+            names,
+            vec!["lo", "enp3s0", "br0", "wlp2s0", "br0.100", "tun0"]
+        );
+
+        let br0 = &networks[2];
+        assert_eq!(br0.inet.as_deref(), Some("192.168.1.20"));
+        assert_eq!(br0.inet_addresses.len(), 2);
+        assert_eq!(br0.stats.unwrap().rx_bytes, 9120);
+
+        // wlp2s0 is not in proc/net/dev, its counters come from sys/class/net.
+        let wlp2s0 = &networks[3];
+        assert_eq!(wlp2s0.stats.unwrap().rx_bytes, 4096);
+        assert_eq!(wlp2s0.index, Some(4));
+        assert_eq!(wlp2s0.link_kind, Some(InterfaceKind::Wireless));
+    }
+
+    #[test]
+    fn does_network_source_read_ifconfig() {
+        let networks = NetworkSource::from_root("tests/fixtures/sosreport-ifconfig")
+            .networks()
+            .unwrap();
+
+        // eth1 is down, so it is left out like on the live host.
+        let names = networks
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["eth0", "lo"]);
+        assert_eq!(networks[0].inet.as_deref(), Some("10.0.0.5"));
+        assert_eq!(networks[0].stats.unwrap().rx_packets, 1200);
+
+        let all = NetworkSource::from_root("tests/fixtures/sosreport-ifconfig")
+            .all_networks()
+            .unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[1].name, "eth1");
+        assert_eq!(all[1].oper_state(), OperState::Down);
+    }
+
+    #[test]
+    fn does_network_source_read_sysfs() {
+        let networks = NetworkSource::from_root("tests/fixtures/sysfs")
+            .networks()
+            .unwrap();
+        assert_eq!(
+            networks,
+            SysfsBackend::with_root("tests/fixtures/sysfs")
+                .networks()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn does_network_source_report_missing_root() {
+        let source = NetworkSource::from_root("tests/fixtures/missing");
+        assert!(!source.is_available());
+        assert!(matches!(source.networks(), Err(NetworkError::Io(_))));
+    }
+}
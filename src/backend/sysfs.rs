@@ -0,0 +1,577 @@
+//! # Sysfs
+//! Reads the network interfaces from the files the Linux kernel
+//! exposes under `/sys/class/net` and `/proc/net`, so it does not
+//! need to spawn a process or open a socket.
+
+use std::fs;
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+
+use crate::backend::{link_type_name, Backend};
+use crate::{
+    parse_proc_net_dev, AddressFlags, Inet6Address, InetAddress, InterfaceFlags, InterfaceKind,
+    InterfaceStats, Netmask, Network, NetworkError, OperState, Scope,
+};
+
+/// # Sysfs Backend
+/// A backend that builds the networks from plain files:
+/// * `/sys/class/net/<name>/`: The `address`, `mtu`, `flags`, `type`,
+///   `operstate`, `carrier`, `ifindex`, `master` and `statistics` of
+///   every interface, and the `uevent` and entries like `wireless`
+///   that tell its kind.
+/// * `/proc/net/if_inet6`: The IPv6 addresses.
+/// * `/proc/net/fib_trie` and `/proc/net/route`: The IPv4 addresses.
+/// * `/proc/net/route` and `/proc/net/ipv6_route`: The default routes.
+///
+/// There is no file that lists the IPv4 addresses of an interface,
+/// so the local addresses are taken from the routing trie and matched
+/// to an interface by the most specific route to them. An address
+/// without a route to its subnet, other than a loopback one, is not
+/// found. Like `ifconfig`, only the interfaces that are up are returned,
+/// unless `all_networks` is used.
+///
+/// The root the files are read from can be changed, to read a copy
+/// of them, like a fake tree in tests.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::{get_networks_with, SysfsBackend};
+///
+/// for network in get_networks_with(&SysfsBackend::with_root("tests/fixtures/sysfs")) {
+///     println!("{}", network);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SysfsBackend {
+    root: PathBuf,
+}
+
+impl SysfsBackend {
+    /// Creates a backend that reads the files of this system.
+    pub fn new() -> SysfsBackend {
+        SysfsBackend::with_root("/")
+    }
+
+    /// Creates a backend that reads the files under the given root,
+    /// which has the `sys` and `proc` directories in it.
+    pub fn with_root(root: impl Into<PathBuf>) -> SysfsBackend {
+        SysfsBackend { root: root.into() }
+    }
+
+    /// The root the files are read from.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Reads every interface, including the ones that are down.
+    pub(crate) fn read_networks(&self) -> Result<Vec<Network>, NetworkError> {
+        let class = self.root.join("sys/class/net");
+
+        let mut names = fs::read_dir(&class)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect::<Vec<String>>();
+        names.sort();
+
+        let proc_stats = read(&self.root.join("proc/net/dev"))
+            .map(|text| parse_proc_net_dev(&text))
+            .unwrap_or_default();
+
+        let mut networks = names
+            .into_iter()
+            .filter(|name| class.join(name).join("ifindex").exists())
+            .map(|name| {
+                let stats = proc_stats
+                    .iter()
+                    .find(|(x, _)| *x == name)
+                    .map(|(_, stats)| *stats);
+                read_link(&class.join(&name), name, stats)
+            })
+            .collect::<Vec<Network>>();
+
+        networks.sort_by_key(|network| network.index);
+
+        if let Some(text) = read(&self.root.join("proc/net/if_inet6")) {
+            parse_if_inet6(&mut networks, &text);
+        }
+
+        if let Some(trie) = read(&self.root.join("proc/net/fib_trie")) {
+            let routes = read(&self.root.join("proc/net/route"))
+                .map(|text| parse_route(&text))
+                .unwrap_or_default();
+            let (addresses, broadcasts) = parse_fib_trie(&trie);
+            add_local_addresses(&mut networks, &addresses, &broadcasts, &routes);
+        }
+
+        Ok(networks)
+    }
+}
+
+impl Default for SysfsBackend {
+    fn default() -> SysfsBackend {
+        SysfsBackend::new()
+    }
+}
+
+impl Backend for SysfsBackend {
+    fn name(&self) -> &str {
+        "sysfs"
+    }
+
+    fn is_available(&self) -> bool {
+        self.root.join("sys/class/net").is_dir()
+    }
+
+    fn networks(&self) -> Result<Vec<Network>, NetworkError> {
+        Ok(self
+            .read_networks()?
+            .into_iter()
+            .filter(|network| network.is_up())
+            .collect())
+    }
+
+    fn all_networks(&self) -> Result<Vec<Network>, NetworkError> {
+        self.read_networks()
+    }
+
+    fn default_routes(&self) -> Result<Vec<String>, NetworkError> {
+        let mut names = read(&self.root.join("proc/net/route"))
+            .map(|text| parse_route(&text))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, destination, mask)| destination.is_unspecified() && mask.is_unspecified())
+            .map(|(name, _, _)| name)
+            .collect::<Vec<String>>();
+
+        if let Some(text) = read(&self.root.join("proc/net/ipv6_route")) {
+            names.extend(parse_ipv6_default_routes(&text));
+        }
+        names.sort();
+        names.dedup();
+
+        Ok(names)
+    }
+}
+
+/// Reads a file, or None if it can not be read.
+fn read(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok()
+}
+
+/// Reads a one line attribute of an interface, like its `mtu`.
+fn attribute(dir: &Path, name: &str) -> Option<String> {
+    read(&dir.join(name)).map(|x| x.trim().to_string())
+}
+
+/// Parses a number like `0x1003` or `1500` from a sysfs attribute.
+fn number(text: &str) -> Option<u32> {
+    match text.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// Internal method to read one interface from its `/sys/class/net` directory.
+/// `stats` are the counters from `/proc/net/dev`, used when the
+/// directory has no `statistics`.
+fn read_link(dir: &Path, name: String, stats: Option<InterfaceStats>) -> Network {
+    let link_type = attribute(dir, "type").and_then(|x| x.parse::<u16>().ok());
+    let raw_flags = attribute(dir, "flags").and_then(|x| number(&x));
+
+    let mut flags = InterfaceFlags::from_bits(raw_flags.unwrap_or_default());
+    // The flags in sysfs are the ones that were set, the kernel adds
+    // RUNNING and LOWER_UP when there is a carrier.
+    let carrier = attribute(dir, "carrier").as_deref() == Some("1")
+        || attribute(dir, "operstate").as_deref() == Some("up");
+    if carrier && flags.contains(InterfaceFlags::UP) {
+        flags.insert(InterfaceFlags::RUNNING | InterfaceFlags::LOWER_UP);
+    }
+
+    let mut network = Network {
+        name,
+        flags,
+        raw_flags,
+        mtu: attribute(dir, "mtu").and_then(|x| number(&x)),
+        index: attribute(dir, "ifindex").and_then(|x| number(&x)),
+        link_type: link_type.map(link_type_name),
+        master: fs::read_link(dir.join("master"))
+            .ok()
+            .and_then(|x| Some(x.file_name()?.to_string_lossy().to_string())),
+        stats: read_statistics(&dir.join("statistics")).or(stats),
+        operstate: attribute(dir, "operstate").and_then(|x| OperState::from_name(&x)),
+        link_kind: read_kind(dir, link_type),
+        ..Default::default()
+    };
+
+    if link_type == Some(1) {
+        network.mac = attribute(dir, "address");
+    }
+
+    network
+}
+
+/// Internal method to work out the kind of an interface from the
+/// entries of its directory: `wireless` or `phy80211` for a wireless
+/// card, the `DEVTYPE` of its `uevent`, like `bridge` or `vlan`, and
+/// `tun_flags` for a tun or tap. An Ethernet link with a `device` is
+/// a real card, one without is some virtual kind that is not known.
+fn read_kind(dir: &Path, link_type: Option<u16>) -> Option<InterfaceKind> {
+    let exists = |name: &str| fs::symlink_metadata(dir.join(name)).is_ok();
+    let devtype = read(&dir.join("uevent")).and_then(|text| {
+        text.lines()
+            .find_map(|line| line.strip_prefix("DEVTYPE="))
+            .and_then(|x| InterfaceKind::from_link_kind(x.trim()))
+    });
+
+    if exists("wireless") || exists("phy80211") {
+        return Some(InterfaceKind::Wireless);
+    }
+    if devtype.is_some() {
+        return devtype;
+    }
+    if exists("bridge") {
+        return Some(InterfaceKind::Bridge);
+    }
+    if exists("bonding") {
+        return Some(InterfaceKind::Bond);
+    }
+    // IFF_TUN is 0x1 and IFF_TAP is 0x2.
+    if let Some(flags) = attribute(dir, "tun_flags").and_then(|x| number(&x)) {
+        return Some(match flags & 0x2 {
+            0 => InterfaceKind::Tun,
+            _ => InterfaceKind::Tap,
+        });
+    }
+
+    match link_type? {
+        1 if exists("device") => Some(InterfaceKind::Ethernet),
+        link_type => InterfaceKind::from_link_type(&link_type_name(link_type)),
+    }
+}
+
+/// Internal method to read the counters in the `statistics` directory of an interface.
+fn read_statistics(dir: &Path) -> Option<InterfaceStats> {
+    if !dir.is_dir() {
+        return None;
+    }
+
+    let counter = |name: &str| {
+        attribute(dir, name)
+            .and_then(|x| x.parse().ok())
+            .unwrap_or_default()
+    };
+
+    Some(InterfaceStats {
+        rx_packets: counter("rx_packets"),
+        rx_bytes: counter("rx_bytes"),
+        rx_errors: counter("rx_errors"),
+        rx_dropped: counter("rx_dropped"),
+        rx_overruns: counter("rx_fifo_errors"),
+        rx_frame: counter("rx_frame_errors"),
+        rx_missed: counter("rx_missed_errors"),
+        rx_compressed: counter("rx_compressed"),
+        multicast: counter("multicast"),
+        tx_packets: counter("tx_packets"),
+        tx_bytes: counter("tx_bytes"),
+        tx_errors: counter("tx_errors"),
+        tx_dropped: counter("tx_dropped"),
+        tx_overruns: counter("tx_fifo_errors"),
+        tx_carrier: counter("tx_carrier_errors"),
+        tx_collisions: counter("collisions"),
+        tx_compressed: counter("tx_compressed"),
+    })
+}
+
+/// Internal method to add the IPv6 addresses of `/proc/net/if_inet6`,
+/// which has a line for every address like:
+///
+/// ```text
+/// fe800000000000005054fffe123456 02 40 20 80   enp3s0
+/// ```
+///
+/// The address, index, prefix length, scope and flags are all hex.
+fn parse_if_inet6(networks: &mut [Network], text: &str) {
+    for line in text.lines() {
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() < 6 {
+            continue;
+        }
+
+        let address = match u128::from_str_radix(fields[0], 16) {
+            Ok(address) => std::net::Ipv6Addr::from(address),
+            Err(_) => continue,
+        };
+        let prefix_len = u8::from_str_radix(fields[2], 16).unwrap_or(128);
+        let flags = u32::from_str_radix(fields[4], 16).unwrap_or_default();
+
+        if let Some(network) = networks.iter_mut().find(|x| x.name == fields[5]) {
+            network.inet6_addresses.push(Inet6Address {
+                scope: Scope::from_scopeid(fields[3]).unwrap_or_else(|| Scope::of(&address)),
+                flags: AddressFlags::from_bits(flags),
+                ..Inet6Address::new(address, prefix_len)
+            });
+        }
+    }
+}
+
+/// Internal method to get the local and broadcast addresses from
+/// `/proc/net/fib_trie`. Every address of the host shows up as a
+/// `/32 host LOCAL` leaf, and every broadcast address the kernel
+/// listens on as a `/32 link BROADCAST` leaf:
+///
+/// ```text
+///            |-- 192.168.1.20
+///               /32 host LOCAL
+///         |-- 192.168.1.255
+///            /32 link BROADCAST
+/// ```
+///
+/// The same address shows up once for every table, so they are deduplicated.
+fn parse_fib_trie(text: &str) -> (Vec<Ipv4Addr>, Vec<Ipv4Addr>) {
+    let mut addresses = Vec::new();
+    let mut broadcasts = Vec::new();
+    let mut leaf = None;
+
+    for line in text.lines() {
+        let line = line.trim();
+
+        if let Some(address) = line.strip_prefix("|-- ") {
+            leaf = address.parse::<Ipv4Addr>().ok();
+        } else if line.starts_with("/32 host LOCAL") {
+            if let Some(address) = leaf {
+                if !addresses.contains(&address) {
+                    addresses.push(address);
+                }
+            }
+        } else if line.starts_with("/32 link BROADCAST") {
+            if let Some(address) = leaf {
+                if !broadcasts.contains(&address) {
+                    broadcasts.push(address);
+                }
+            }
+        }
+    }
+
+    (addresses, broadcasts)
+}
+
+/// A line of `/proc/net/route`: the interface, destination and mask.
+type Route = (String, Ipv4Addr, Ipv4Addr);
+
+/// Internal method to parse the routes of `/proc/net/route`:
+///
+/// ```text
+/// Iface   Destination Gateway  Flags RefCnt Use Metric Mask     MTU Window IRTT
+/// br0     0001A8C0    00000000 0001  0      0   425    00FFFFFF 0   0      0
+/// ```
+fn parse_route(text: &str) -> Vec<Route> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            if fields.len() < 8 {
+                return None;
+            }
+
+            Some((
+                fields[0].to_string(),
+                route_addr(fields[1])?,
+                route_addr(fields[7])?,
+            ))
+        })
+        .collect()
+}
+
+/// Internal method to find the interfaces with a default route in
+/// `/proc/net/ipv6_route`, whose destination and prefix length are
+/// all zeros. The kernel keeps an unreachable default route on `lo`,
+/// which has the `RTF_REJECT` flag and is left out.
+///
+/// ```text
+/// 00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000001 00000000 00450003 br0
+/// ```
+fn parse_ipv6_default_routes(text: &str) -> Vec<String> {
+    const RTF_REJECT: u32 = 0x0200;
+
+    text.lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            if fields.len() < 10 || fields[1] != "00" || fields[0].bytes().any(|x| x != b'0') {
+                return None;
+            }
+
+            let flags = u32::from_str_radix(fields[8], 16).ok()?;
+            match flags & RTF_REJECT {
+                0 => Some(fields[9].to_string()),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// The addresses in `/proc/net/route` are printed as the raw
+/// network order value in the byte order of the host.
+fn route_addr(text: &str) -> Option<Ipv4Addr> {
+    u32::from_str_radix(text, 16)
+        .ok()
+        .map(|x| Ipv4Addr::from(x.to_ne_bytes()))
+}
+
+/// Internal method to add every local address to the interface with
+/// the most specific route to it. Loopback addresses have no route
+/// in the main table, so they go to the loopback interface as a /8.
+/// The broadcast address is the `BROADCAST` leaf in the same subnet,
+/// if the kernel has one.
+fn add_local_addresses(
+    networks: &mut [Network],
+    addresses: &[Ipv4Addr],
+    broadcasts: &[Ipv4Addr],
+    routes: &[Route],
+) {
+    for address in addresses {
+        let route = routes
+            .iter()
+            .filter(|(_, destination, mask)| {
+                !mask.is_unspecified()
+                    && u32::from(*address) & u32::from(*mask) == u32::from(*destination)
+            })
+            .max_by_key(|(_, _, mask)| u32::from(*mask).count_ones());
+
+        let (network, prefix_len) = match route {
+            Some((name, _, mask)) => (
+                networks.iter_mut().find(|x| x.name == *name),
+                Netmask::try_from(*mask)
+                    .map(|x| x.prefix_len())
+                    .unwrap_or(32),
+            ),
+            None if address.is_loopback() => (networks.iter_mut().find(|x| x.is_loopback()), 8),
+            None => continue,
+        };
+
+        if let Some(network) = network {
+            let mut inet = InetAddress::new(*address, prefix_len);
+            if network.flags.contains(InterfaceFlags::BROADCAST) {
+                inet.broadcast = subnet_broadcast(*address, prefix_len, broadcasts);
+            }
+            network.push_inet(inet);
+        }
+    }
+}
+
+/// Internal method to find the broadcast address of the subnet of
+/// `address` among the `BROADCAST` leaves. Older kernels also add
+/// the network address itself as one, so it is skipped.
+fn subnet_broadcast(
+    address: Ipv4Addr,
+    prefix_len: u8,
+    broadcasts: &[Ipv4Addr],
+) -> Option<Ipv4Addr> {
+    let mask = u32::MAX
+        .checked_shl(32 - u32::from(prefix_len))
+        .unwrap_or(0);
+    let network = u32::from(address) & mask;
+    broadcasts
+        .iter()
+        .filter(|x| u32::from(**x) & mask == network && u32::from(**x) != network)
+        .max()
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_sysfs_backend_work() {
+        let backend = SysfsBackend::with_root("tests/fixtures/sysfs");
+        assert!(backend.is_available());
+
+        let networks = backend.networks().unwrap();
+        let names = networks
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["lo", "enp3s0", "br0"]);
+
+        let lo = &networks[0];
+        assert!(lo.is_loopback());
+        assert!(lo.is_running());
+        assert_eq!(lo.link_type.as_deref(), Some("loopback"));
+        assert_eq!(lo.mac, None);
+        assert_eq!(lo.inet.as_deref(), Some("127.0.0.1"));
+        assert_eq!(lo.netmask.as_deref(), Some("255.0.0.0"));
+        assert_eq!(lo.inet6_addresses[0].scope, Scope::Host);
+
+        let enp3s0 = &networks[1];
+        assert_eq!(enp3s0.index, Some(2));
+        assert_eq!(enp3s0.mtu, Some(1500));
+        assert_eq!(enp3s0.mac.as_deref(), Some("52:54:00:12:34:56"));
+        assert_eq!(enp3s0.master.as_deref(), Some("br0"));
+        assert!(enp3s0.flags.contains(InterfaceFlags::LOWER_UP));
+        assert_eq!(enp3s0.stats.unwrap().rx_packets, 5021);
+        assert_eq!(enp3s0.stats.unwrap().tx_carrier, 2);
+        assert!(enp3s0.inet.is_none());
+
+        let br0 = &networks[2];
+        assert_eq!(br0.inet.as_deref(), Some("192.168.1.20"));
+        assert_eq!(br0.netmask.as_deref(), Some("255.255.255.0"));
+        assert_eq!(br0.broadcast.as_deref(), Some("192.168.1.255"));
+        assert_eq!(br0.inet_addresses.len(), 2);
+        assert_eq!(br0.inet6_addresses.len(), 2);
+        assert!(br0.inet6_addresses[0].is_temporary());
+        assert_eq!(br0.inet6_addresses[1].scope, Scope::Link);
+        // There is no statistics directory, so the counters come from /proc/net/dev.
+        assert_eq!(br0.stats.unwrap().rx_bytes, 9120);
+    }
+
+    #[test]
+    fn does_subnet_broadcast_work() {
+        let broadcasts = [
+            Ipv4Addr::new(10, 0, 0, 0),
+            Ipv4Addr::new(10, 0, 0, 127),
+            Ipv4Addr::new(127, 255, 255, 255),
+        ];
+        let address = Ipv4Addr::new(10, 0, 0, 5);
+        assert_eq!(
+            subnet_broadcast(address, 25, &broadcasts),
+            Some(Ipv4Addr::new(10, 0, 0, 127))
+        );
+        // Nothing is made up when the kernel has no broadcast leaf.
+        assert_eq!(subnet_broadcast(address, 30, &broadcasts), None);
+        assert_eq!(subnet_broadcast(address, 32, &broadcasts), None);
+    }
+
+    #[test]
+    fn does_sysfs_backend_include_down_interfaces() {
+        let networks = SysfsBackend::with_root("tests/fixtures/sysfs")
+            .all_networks()
+            .unwrap();
+        assert_eq!(networks.len(), 4);
+
+        let wlp2s0 = networks.iter().find(|x| x.name == "wlp2s0").unwrap();
+        assert!(!wlp2s0.is_up());
+        assert!(!wlp2s0.is_running());
+        assert_eq!(wlp2s0.operstate, Some(OperState::Down));
+        assert_eq!(wlp2s0.link_kind, Some(InterfaceKind::Wireless));
+
+        let kinds = networks.iter().map(|x| x.kind()).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                InterfaceKind::Loopback,
+                InterfaceKind::Ethernet,
+                InterfaceKind::Bridge,
+                InterfaceKind::Wireless
+            ]
+        );
+        assert_eq!(networks[1].operstate, Some(OperState::Up));
+    }
+
+    #[test]
+    fn does_sysfs_backend_report_missing_root() {
+        let backend = SysfsBackend::with_root("tests/fixtures/missing");
+        assert!(!backend.is_available());
+        assert!(matches!(backend.networks(), Err(NetworkError::Io(_))));
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt::Display;
+
+use crate::Diagnostic;
+
+/// # Network Error
+/// The error returned by the fallible methods of this crate,
+/// like `try_get_networks` and `try_find_network`.
+///
+/// Here is what can go wrong:
+/// * CommandNotFound: The command, like `ifconfig`, is not installed.
+/// * CommandFailed: The command exited with a non-zero status.
+/// * InvalidUtf8: The command printed something that is not UTF-8.
+/// * Parse: The output could not be parsed into a Network.
+/// * Syntax: The output is not what it should look like, see `parse_network_strict`.
+/// * PermissionDenied: Not allowed to run the command or open the socket.
+/// * Io: Any other I/O error.
+#[derive(Debug)]
+pub enum NetworkError {
+    CommandNotFound {
+        command: String,
+    },
+    CommandFailed {
+        command: String,
+        code: Option<i32>,
+        stderr: String,
+    },
+    InvalidUtf8 {
+        command: String,
+    },
+    Parse(String),
+    Syntax(Vec<Diagnostic>),
+    PermissionDenied(String),
+    Io(std::io::Error),
+}
+
+impl Display for NetworkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkError::CommandNotFound { command } => {
+                write!(f, "command not found: {}", command)
+            }
+            NetworkError::CommandFailed {
+                command,
+                code,
+                stderr,
+            } => {
+                match code {
+                    Some(code) => write!(f, "{} exited with status {}", command, code)?,
+                    None => write!(f, "{} was terminated by a signal", command)?,
+                }
+                if !stderr.trim().is_empty() {
+                    write!(f, ": {}", stderr.trim())?;
+                }
+                Ok(())
+            }
+            NetworkError::InvalidUtf8 { command } => {
+                write!(f, "{} printed output that is not valid UTF-8", command)
+            }
+            NetworkError::Parse(message) => write!(f, "failed to parse network: {}", message),
+            NetworkError::Syntax(diagnostics) => {
+                write!(f, "failed to parse network")?;
+                for (i, diagnostic) in diagnostics.iter().enumerate() {
+                    write!(f, "{} {}", if i == 0 { ":" } else { ";" }, diagnostic)?;
+                }
+                Ok(())
+            }
+            NetworkError::PermissionDenied(what) => write!(f, "permission denied: {}", what),
+            NetworkError::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NetworkError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for NetworkError {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::PermissionDenied => {
+                NetworkError::PermissionDenied(error.to_string())
+            }
+            _ => NetworkError::Io(error),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt::Display;
+
+/// # Interface Flags
+/// The flags of a network interface, like whether it is up or
+/// a loopback interface. The values are the same as the Linux
+/// `IFF_*` flags, which is also what Linux `ifconfig` prints
+/// as the number in `flags=4163<UP,BROADCAST,RUNNING,MULTICAST>`.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::InterfaceFlags;
+///
+/// let flags = InterfaceFlags::from_bits(4163);
+///
+/// assert!(flags.contains(InterfaceFlags::UP | InterfaceFlags::RUNNING));
+/// assert_eq!(flags.to_string(), "UP,BROADCAST,RUNNING,MULTICAST");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct InterfaceFlags(u32);
+
+impl InterfaceFlags {
+    pub const UP: InterfaceFlags = InterfaceFlags(0x1);
+    pub const BROADCAST: InterfaceFlags = InterfaceFlags(0x2);
+    pub const DEBUG: InterfaceFlags = InterfaceFlags(0x4);
+    pub const LOOPBACK: InterfaceFlags = InterfaceFlags(0x8);
+    pub const POINTOPOINT: InterfaceFlags = InterfaceFlags(0x10);
+    pub const NOTRAILERS: InterfaceFlags = InterfaceFlags(0x20);
+    pub const RUNNING: InterfaceFlags = InterfaceFlags(0x40);
+    pub const NOARP: InterfaceFlags = InterfaceFlags(0x80);
+    pub const PROMISC: InterfaceFlags = InterfaceFlags(0x100);
+    pub const ALLMULTI: InterfaceFlags = InterfaceFlags(0x200);
+    pub const MASTER: InterfaceFlags = InterfaceFlags(0x400);
+    pub const SLAVE: InterfaceFlags = InterfaceFlags(0x800);
+    pub const MULTICAST: InterfaceFlags = InterfaceFlags(0x1000);
+    pub const PORTSEL: InterfaceFlags = InterfaceFlags(0x2000);
+    pub const AUTOMEDIA: InterfaceFlags = InterfaceFlags(0x4000);
+    pub const DYNAMIC: InterfaceFlags = InterfaceFlags(0x8000);
+    pub const LOWER_UP: InterfaceFlags = InterfaceFlags(0x10000);
+    pub const DORMANT: InterfaceFlags = InterfaceFlags(0x20000);
+    pub const ECHO: InterfaceFlags = InterfaceFlags(0x40000);
+
+    const NAMES: [(InterfaceFlags, &'static str); 19] = [
+        (InterfaceFlags::UP, "UP"),
+        (InterfaceFlags::BROADCAST, "BROADCAST"),
+        (InterfaceFlags::DEBUG, "DEBUG"),
+        (InterfaceFlags::LOOPBACK, "LOOPBACK"),
+        (InterfaceFlags::POINTOPOINT, "POINTOPOINT"),
+        (InterfaceFlags::NOTRAILERS, "NOTRAILERS"),
+        (InterfaceFlags::RUNNING, "RUNNING"),
+        (InterfaceFlags::NOARP, "NOARP"),
+        (InterfaceFlags::PROMISC, "PROMISC"),
+        (InterfaceFlags::ALLMULTI, "ALLMULTI"),
+        (InterfaceFlags::MASTER, "MASTER"),
+        (InterfaceFlags::SLAVE, "SLAVE"),
+        (InterfaceFlags::MULTICAST, "MULTICAST"),
+        (InterfaceFlags::PORTSEL, "PORTSEL"),
+        (InterfaceFlags::AUTOMEDIA, "AUTOMEDIA"),
+        (InterfaceFlags::DYNAMIC, "DYNAMIC"),
+        (InterfaceFlags::LOWER_UP, "LOWER_UP"),
+        (InterfaceFlags::DORMANT, "DORMANT"),
+        (InterfaceFlags::ECHO, "ECHO"),
+    ];
+
+    /// Creates the flags from their raw `IFF_*` bits.
+    pub fn from_bits(bits: u32) -> InterfaceFlags {
+        InterfaceFlags(bits)
+    }
+
+    /// The raw `IFF_*` bits of the flags.
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Gets a flag from its name, like `UP` or `LOOPBACK`.
+    /// Names that are not known, like the BSD only `SIMPLEX`,
+    /// give `None`.
+    pub fn from_name(name: &str) -> Option<InterfaceFlags> {
+        InterfaceFlags::NAMES
+            .iter()
+            .find(|(_, x)| x.eq_ignore_ascii_case(name))
+            .map(|(flag, _)| *flag)
+    }
+
+    /// Gets the flags from a list of names, like the
+    /// `UP,BROADCAST,RUNNING,MULTICAST` inside the angle brackets
+    /// that `ifconfig` and `ip` print.
+    pub fn from_names<'a>(names: impl IntoIterator<Item = &'a str>) -> InterfaceFlags {
+        names
+            .into_iter()
+            .filter_map(InterfaceFlags::from_name)
+            .fold(InterfaceFlags::default(), |flags, flag| flags | flag)
+    }
+
+    /// Whether all of the given flags are set.
+    pub fn contains(&self, other: InterfaceFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Sets the given flags.
+    pub fn insert(&mut self, other: InterfaceFlags) {
+        self.0 |= other.0;
+    }
+
+    /// Whether no flag is set.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl std::ops::BitOr for InterfaceFlags {
+    type Output = InterfaceFlags;
+
+    fn bitor(self, other: InterfaceFlags) -> InterfaceFlags {
+        InterfaceFlags(self.0 | other.0)
+    }
+}
+
+impl Display for InterfaceFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = InterfaceFlags::NAMES
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect::<Vec<&str>>();
+
+        write!(f, "{}", names.join(","))
+    }
+}
+
+/// # Oper State
+/// The operational state of a network interface, as described in
+/// RFC 2863 and reported by Linux in `operstate`. Unlike the `UP`
+/// flag, which is set by the administrator, this tells whether the
+/// interface can actually pass traffic, like whether a cable is
+/// plugged in.
+/// * Unknown: The driver does not report it, like for loopback.
+/// * NotPresent: Some of the hardware is missing.
+/// * Down: The interface can not pass traffic, like without a carrier.
+/// * LowerLayerDown: An interface it is stacked on is down.
+/// * Testing: The interface is in a test mode.
+/// * Dormant: The interface is waiting for something, like 802.1X.
+/// * Up: The interface can pass traffic.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::OperState;
+///
+/// assert_eq!(OperState::from_name("lowerlayerdown"), Some(OperState::LowerLayerDown));
+/// assert_eq!(OperState::Up.to_string(), "UP");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OperState {
+    Unknown,
+    NotPresent,
+    Down,
+    LowerLayerDown,
+    Testing,
+    Dormant,
+    Up,
+}
+
+impl OperState {
+    /// The states in the order of the Linux `IF_OPER_*` values.
+    const STATES: [(OperState, &'static str); 7] = [
+        (OperState::Unknown, "UNKNOWN"),
+        (OperState::NotPresent, "NOTPRESENT"),
+        (OperState::Down, "DOWN"),
+        (OperState::LowerLayerDown, "LOWERLAYERDOWN"),
+        (OperState::Testing, "TESTING"),
+        (OperState::Dormant, "DORMANT"),
+        (OperState::Up, "UP"),
+    ];
+
+    /// Gets a state from the name `ip` and `/sys` use, like `UP`
+    /// or `lowerlayerdown`.
+    pub fn from_name(name: &str) -> Option<OperState> {
+        OperState::STATES
+            .iter()
+            .find(|(_, x)| x.eq_ignore_ascii_case(name))
+            .map(|(state, _)| *state)
+    }
+
+    /// Gets a state from its Linux `IF_OPER_*` value.
+    pub(crate) fn from_value(value: u8) -> Option<OperState> {
+        OperState::STATES
+            .get(value as usize)
+            .map(|(state, _)| *state)
+    }
+}
+
+impl Display for OperState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = OperState::STATES
+            .iter()
+            .find(|(state, _)| state == self)
+            .map_or("UNKNOWN", |(_, name)| name);
+
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_from_names_work() {
+        let flags = InterfaceFlags::from_names("UP,LOOPBACK,RUNNING,SIMPLEX".split(','));
+
+        assert_eq!(flags.bits(), 73);
+        assert!(flags.contains(InterfaceFlags::LOOPBACK));
+        assert!(!flags.contains(InterfaceFlags::BROADCAST));
+    }
+
+    #[test]
+    fn does_oper_state_work() {
+        assert_eq!(OperState::from_name("UP"), Some(OperState::Up));
+        assert_eq!(OperState::from_name("dormant"), Some(OperState::Dormant));
+        assert_eq!(OperState::from_name("active"), None);
+        assert_eq!(OperState::from_value(2), Some(OperState::Down));
+        assert_eq!(OperState::from_value(7), None);
+        assert_eq!(OperState::NotPresent.to_string(), "NOTPRESENT");
+    }
+}
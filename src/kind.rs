@@ -0,0 +1,246 @@
+use std::fmt::Display;
+
+/// # Interface Kind
+/// What kind of device a network interface is, like a wireless card
+/// or a bridge. It is read from what the kernel knows about the
+/// interface where the source has it, like the link info of `ip -d`
+/// or the `uevent` in `/sys/class/net`, and otherwise guessed from
+/// the name, see `Network::kind`.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::InterfaceKind;
+///
+/// assert_eq!(InterfaceKind::from_name("wlx00c0ca123456"), InterfaceKind::Wireless);
+/// assert_eq!(InterfaceKind::from_name("veth1a2b3c"), InterfaceKind::Veth);
+/// assert_eq!(InterfaceKind::Ethernet.to_string(), "ethernet");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InterfaceKind {
+    Ethernet,
+    Wireless,
+    Loopback,
+    Bridge,
+    Bond,
+    Vlan,
+    Veth,
+    Tun,
+    Tap,
+    WireGuard,
+    Ppp,
+    Wwan,
+    Infiniband,
+    Can,
+    Dummy,
+    Other,
+}
+
+impl InterfaceKind {
+    const NAMES: [(InterfaceKind, &'static str); 16] = [
+        (InterfaceKind::Ethernet, "ethernet"),
+        (InterfaceKind::Wireless, "wireless"),
+        (InterfaceKind::Loopback, "loopback"),
+        (InterfaceKind::Bridge, "bridge"),
+        (InterfaceKind::Bond, "bond"),
+        (InterfaceKind::Vlan, "vlan"),
+        (InterfaceKind::Veth, "veth"),
+        (InterfaceKind::Tun, "tun"),
+        (InterfaceKind::Tap, "tap"),
+        (InterfaceKind::WireGuard, "wireguard"),
+        (InterfaceKind::Ppp, "ppp"),
+        (InterfaceKind::Wwan, "wwan"),
+        (InterfaceKind::Infiniband, "infiniband"),
+        (InterfaceKind::Can, "can"),
+        (InterfaceKind::Dummy, "dummy"),
+        (InterfaceKind::Other, "other"),
+    ];
+
+    /// Gets the kind from the kind of link the kernel reports in the
+    /// link info, like `bridge` or `vlan` in `ip -d link`, or from the
+    /// `DEVTYPE` of the `uevent` in `/sys/class/net`, like `wlan`.
+    /// A `tun` link is a Tun, unless its type says it is a tap.
+    /// Kinds that are not known give None.
+    pub fn from_link_kind(kind: &str) -> Option<InterfaceKind> {
+        let kind = match kind {
+            "wlan" => InterfaceKind::Wireless,
+            "bridge" => InterfaceKind::Bridge,
+            "bond" => InterfaceKind::Bond,
+            "vlan" => InterfaceKind::Vlan,
+            "veth" => InterfaceKind::Veth,
+            "tun" => InterfaceKind::Tun,
+            "wireguard" => InterfaceKind::WireGuard,
+            "ppp" => InterfaceKind::Ppp,
+            "wwan" => InterfaceKind::Wwan,
+            "can" | "vcan" | "vxcan" => InterfaceKind::Can,
+            "dummy" => InterfaceKind::Dummy,
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+
+    /// Gets the kind from the type of link, as `ip` prints it after
+    /// `link/`, like `loopback` or `ieee802.11`. Ethernet links are
+    /// not told apart from the other kinds that look like Ethernet,
+    /// like bridges, so they give None.
+    pub(crate) fn from_link_type(link_type: &str) -> Option<InterfaceKind> {
+        let kind = match link_type {
+            "loopback" => InterfaceKind::Loopback,
+            "ieee802.11" | "ieee802.11/radiotap" => InterfaceKind::Wireless,
+            "infiniband" => InterfaceKind::Infiniband,
+            "can" => InterfaceKind::Can,
+            "ppp" => InterfaceKind::Ppp,
+            "rawip" => InterfaceKind::Wwan,
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+
+    /// Whether interfaces of this kind are made up by the kernel,
+    /// like a bridge or a tunnel, rather than backed by a device.
+    /// Other is not, since nothing is known about it.
+    pub fn is_virtual(&self) -> bool {
+        matches!(
+            self,
+            InterfaceKind::Loopback
+                | InterfaceKind::Bridge
+                | InterfaceKind::Bond
+                | InterfaceKind::Vlan
+                | InterfaceKind::Veth
+                | InterfaceKind::Tun
+                | InterfaceKind::Tap
+                | InterfaceKind::WireGuard
+                | InterfaceKind::Dummy
+        )
+    }
+
+    /// Guesses the kind from the name of an interface, from the names
+    /// systemd, the kernel and the BSDs give them, like `wlp2s0`,
+    /// `enx00e04c680001` or `em0`. This is only a guess, renamed
+    /// interfaces can be anything.
+    pub fn from_name(name: &str) -> InterfaceKind {
+        // An alias, like `eth0:1`, is the same kind as its interface.
+        let name = name.split(':').next().unwrap_or(name);
+        let prefixes = [
+            ("veth", InterfaceKind::Veth),
+            ("wlan", InterfaceKind::Wireless),
+            ("wl", InterfaceKind::Wireless),
+            ("wifi", InterfaceKind::Wireless),
+            ("ath", InterfaceKind::Wireless),
+            ("virbr", InterfaceKind::Bridge),
+            ("bridge", InterfaceKind::Bridge),
+            ("br", InterfaceKind::Bridge),
+            ("bond", InterfaceKind::Bond),
+            ("vlan", InterfaceKind::Vlan),
+            ("utun", InterfaceKind::Tun),
+            ("tun", InterfaceKind::Tun),
+            ("tap", InterfaceKind::Tap),
+            ("wg", InterfaceKind::WireGuard),
+            ("ppp", InterfaceKind::Ppp),
+            ("wwan", InterfaceKind::Wwan),
+            ("wwp", InterfaceKind::Wwan),
+            ("ib", InterfaceKind::Infiniband),
+            ("vcan", InterfaceKind::Can),
+            ("can", InterfaceKind::Can),
+            ("dummy", InterfaceKind::Dummy),
+            ("eth", InterfaceKind::Ethernet),
+            ("en", InterfaceKind::Ethernet),
+            ("em", InterfaceKind::Ethernet),
+            ("igb", InterfaceKind::Ethernet),
+            ("ixgbe", InterfaceKind::Ethernet),
+        ];
+
+        if name == "lo" || name == "lo0" {
+            return InterfaceKind::Loopback;
+        }
+
+        // A VLAN is named after its parent and id, like `eth0.100`.
+        if let Some((parent, id)) = name.rsplit_once('.') {
+            if !parent.is_empty() && !id.is_empty() && id.bytes().all(|x| x.is_ascii_digit()) {
+                return InterfaceKind::Vlan;
+            }
+        }
+
+        prefixes
+            .iter()
+            .find(|(prefix, _)| name.starts_with(prefix))
+            .map_or(InterfaceKind::Other, |(_, kind)| *kind)
+    }
+}
+
+impl Display for InterfaceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = InterfaceKind::NAMES
+            .iter()
+            .find(|(kind, _)| kind == self)
+            .map_or("other", |(_, name)| name);
+
+        write!(f, "{}", name)
+    }
+}
+
+/// # Unaddressed
+/// Whether `get_by_kind` keeps the interfaces that have no IPv4
+/// address, like a bridge port, an IPv6 only link or a tunnel
+/// that is not configured yet.
+/// * Exclude: Only the interfaces with an IPv4 address are kept,
+///   like `get_wlan` and `get_ethernet` do. This is the default.
+/// * Include: Every interface of the kind is kept.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Unaddressed {
+    #[default]
+    Exclude,
+    Include,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_from_name_work() {
+        let names = [
+            ("wlp2s0", InterfaceKind::Wireless),
+            ("wlx00c0ca123456", InterfaceKind::Wireless),
+            ("wlan0", InterfaceKind::Wireless),
+            ("enp3s0", InterfaceKind::Ethernet),
+            ("ens18", InterfaceKind::Ethernet),
+            ("eno1", InterfaceKind::Ethernet),
+            ("enx00e04c680001", InterfaceKind::Ethernet),
+            ("eth0:1", InterfaceKind::Ethernet),
+            ("em0", InterfaceKind::Ethernet),
+            ("veth1a2b3c", InterfaceKind::Veth),
+            ("eth0.100", InterfaceKind::Vlan),
+            ("br0", InterfaceKind::Bridge),
+            ("virbr0", InterfaceKind::Bridge),
+            ("docker0", InterfaceKind::Other),
+            ("utun0", InterfaceKind::Tun),
+            ("wg0", InterfaceKind::WireGuard),
+            ("lo", InterfaceKind::Loopback),
+        ];
+
+        for (name, kind) in names {
+            assert_eq!(InterfaceKind::from_name(name), kind, "{}", name);
+        }
+    }
+
+    #[test]
+    fn does_from_link_kind_work() {
+        assert_eq!(
+            InterfaceKind::from_link_kind("wlan"),
+            Some(InterfaceKind::Wireless)
+        );
+        assert_eq!(
+            InterfaceKind::from_link_kind("veth"),
+            Some(InterfaceKind::Veth)
+        );
+        assert_eq!(InterfaceKind::from_link_kind("macvlan"), None);
+        assert_eq!(
+            InterfaceKind::from_link_type("ieee802.11"),
+            Some(InterfaceKind::Wireless)
+        );
+        assert_eq!(InterfaceKind::from_link_type("ether"), None);
+    }
+}
@@ -1,4 +1,36 @@
+use std::collections::BTreeMap;
 use std::fmt::Display;
+use std::net::{IpAddr, Ipv4Addr};
+
+mod addr;
+mod backend;
+mod error;
+mod flags;
+mod kind;
+mod parser;
+mod query;
+mod stats;
+
+pub use addr::{
+    AddressFlags, Cidr, Inet6Address, Inet6Flags, InetAddress, Lifetime, MacAddr, Netmask, Scope,
+};
+#[cfg(unix)]
+pub use backend::GetifaddrsBackend;
+#[cfg(target_os = "linux")]
+pub use backend::NetlinkBackend;
+pub use backend::{
+    available_backends, detect_backend, Backend, FixtureBackend, IfconfigBackend, IpBackend,
+    NetworkSource, SysfsBackend,
+};
+pub use error::NetworkError;
+pub use flags::{InterfaceFlags, OperState};
+pub use kind::{InterfaceKind, Unaddressed};
+pub use parser::{
+    from_file, from_reader, parse_ip_json, parse_ip_text, parse_network, parse_network_lenient,
+    parse_network_strict, parse_networks, Diagnostic,
+};
+pub use query::{MatchMode, NetworkQuery};
+pub use stats::{parse_proc_net_dev, InterfaceStats};
 
 /// # Network
 /// Represents a network interface with it's associated information.
@@ -9,22 +41,201 @@ use std::fmt::Display;
 /// * broadcast: The broadcast address of the network interface.
 /// * netmask: The netmask of the network interface.
 /// * mac: The MAC address of the network interface.
+/// * inet_addresses: All IPv4 addresses of the network interface.
+/// * inet6_addresses: All IPv6 addresses of the network interface.
+/// * flags: The flags of the network interface, like UP or LOOPBACK.
+/// * raw_flags: The flags as the number the source reported.
+/// * mtu: The maximum transmission unit of the network interface.
+/// * stats: The RX and TX traffic counters of the network interface.
+/// * index: The index the kernel gave the network interface.
+/// * link_type: The type of the link, like `ether` or `loopback`.
+/// * master: The interface this one is enslaved to, like a bridge or bond.
+/// * label: The label of an alias, like `eth0:1`, which is also its name.
+/// * parent: The interface this one is stacked on, like `eth0` for the
+///   VLAN `eth0.100` or the alias `eth0:1`.
+/// * peer: The link `ip` prints after the `@` of the name, like `eth0` of
+///   `eth0.100@eth0`, or `if5` for the peer of a veth in another namespace.
+/// * operstate: The operational state the source reported, like `UP`
+///   or `DOWN`, see `oper_state` for one that is always there.
+/// * link_kind: The kind of interface the source reported, like a bridge
+///   or a wireless card, see `kind` for one that is always there.
+/// * altnames: The alternative names of the network interface.
+/// * raw: The text the network interface was parsed from, as it was given.
+/// * extra: The fields of that text the parser does not know, like `txqueuelen`.
+/// 
+/// The `inet`, `broadcast` and `netmask` fields describe the primary
+/// address, which is the first one in `inet_addresses`. Secondary
+/// addresses and aliases are only found in `inet_addresses`.
+/// 
+/// The fields are kept as the text the source printed, use the
+/// typed accessors like `inet_addr` and `mac_addr` to get them as
+/// addresses, without having to parse them yourself.
 /// 
-/// This struct is only representational in function and does not
-/// contain any methods.
-#[derive(Clone)]
+/// When a network is parsed from text, nothing of it is lost: `raw`
+/// is exactly the text that was parsed, so joining the `raw` of every
+/// network `parse_networks` returns gives back the whole text.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Network {
     pub name: String,
     pub inet: Option<String>,
     pub broadcast: Option<String>,
     pub netmask: Option<String>,
     pub mac: Option<String>,
+    pub inet_addresses: Vec<InetAddress>,
+    pub inet6_addresses: Vec<Inet6Address>,
+    pub flags: InterfaceFlags,
+    pub raw_flags: Option<u32>,
+    pub mtu: Option<u32>,
+    pub stats: Option<InterfaceStats>,
+    pub index: Option<u32>,
+    pub link_type: Option<String>,
+    pub master: Option<String>,
+    pub label: Option<String>,
+    pub parent: Option<String>,
+    pub peer: Option<String>,
+    pub operstate: Option<OperState>,
+    pub link_kind: Option<InterfaceKind>,
+    pub altnames: Vec<String>,
+    pub raw: Option<String>,
+    pub extra: BTreeMap<String, String>,
+}
+
+impl Network {
+    /// The primary IPv4 address, if it is a valid address.
+    pub fn inet_addr(&self) -> Option<Ipv4Addr> {
+        self.inet.as_deref()?.parse().ok()
+    }
+
+    /// The broadcast address of the primary IPv4 address,
+    /// if it is a valid address.
+    pub fn broadcast_addr(&self) -> Option<Ipv4Addr> {
+        self.broadcast.as_deref()?.parse().ok()
+    }
+
+    /// The netmask of the primary IPv4 address, if it is a
+    /// valid netmask.
+    pub fn netmask_addr(&self) -> Option<Netmask> {
+        self.netmask.as_deref()?.parse().ok()
+    }
+
+    /// The MAC address, if it is a valid 48 bit address.
+    pub fn mac_addr(&self) -> Option<MacAddr> {
+        self.mac.as_deref()?.parse().ok()
+    }
+
+    /// Whether the network interface is up, which means it was
+    /// enabled by the administrator.
+    pub fn is_up(&self) -> bool {
+        self.flags.contains(InterfaceFlags::UP)
+    }
+
+    /// Whether the network interface is running, which means it
+    /// is up and the link is ready to carry traffic.
+    pub fn is_running(&self) -> bool {
+        self.flags.contains(InterfaceFlags::RUNNING)
+    }
+
+    /// The operational state of the network interface. If the source
+    /// did not report it, like `ifconfig` on Linux, it is worked out
+    /// from the flags: an interface that is not up is down, and one
+    /// that is up but not running has no carrier, so it is down too.
+    pub fn oper_state(&self) -> OperState {
+        match self.operstate {
+            Some(state) => state,
+            None if self.is_up() && self.is_running() => OperState::Up,
+            None => OperState::Down,
+        }
+    }
+
+    /// The kind of the network interface. If the source did not
+    /// report it, like `ifconfig`, it is worked out from the flags
+    /// and link type, and as a last resort guessed from the name
+    /// with `InterfaceKind::from_name`.
+    pub fn kind(&self) -> InterfaceKind {
+        if let Some(kind) = self.link_kind {
+            return kind;
+        }
+        if self.is_loopback() {
+            return InterfaceKind::Loopback;
+        }
+
+        self.link_type
+            .as_deref()
+            .and_then(InterfaceKind::from_link_type)
+            .unwrap_or_else(|| InterfaceKind::from_name(&self.name))
+    }
+
+    /// Whether this is a loopback interface, like `lo`.
+    pub fn is_loopback(&self) -> bool {
+        self.flags.contains(InterfaceFlags::LOOPBACK)
+    }
+
+    /// Whether this is a point-to-point interface, like a VPN tunnel.
+    pub fn is_point_to_point(&self) -> bool {
+        self.flags.contains(InterfaceFlags::POINTOPOINT)
+    }
+
+    /// All IPv4 and IPv6 addresses of the network interface.
+    pub fn ip_addrs(&self) -> Vec<IpAddr> {
+        self.inet_addresses
+            .iter()
+            .map(|x| IpAddr::V4(x.address))
+            .chain(self.inet6_addresses.iter().map(|x| IpAddr::V6(x.address)))
+            .collect()
+    }
+
+    /// Adds an IPv4 address to the network, making it the
+    /// primary address if it is the first one.
+    pub(crate) fn push_inet(&mut self, address: InetAddress) {
+        if self.inet_addresses.is_empty() {
+            self.inet = Some(address.address.to_string());
+            self.netmask = Some(address.netmask().to_string());
+            self.broadcast = address.broadcast.map(|broadcast| broadcast.to_string());
+        }
+
+        self.inet_addresses.push(address);
+    }
+
+    /// Sets the link printed after the `@` of the name. Unless the link
+    /// is in another namespace, like `if5`, or there is none, like
+    /// `NONE`, it is also the parent.
+    pub(crate) fn set_peer(&mut self, peer: &str) {
+        let elsewhere = peer == "NONE"
+            || peer
+                .strip_prefix("if")
+                .is_some_and(|x| !x.is_empty() && x.bytes().all(|x| x.is_ascii_digit()));
+
+        self.peer = Some(peer.to_string());
+        if !elsewhere {
+            self.parent = Some(peer.to_string());
+        }
+    }
 }
 
 impl Display for Network {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut output = format!("name: {}", self.name);
 
+        if !self.flags.is_empty() {
+            output = format!("{}\nflags: {}", output, self.flags);
+        }
+
+        if let Some(state) = &self.operstate {
+            output = format!("{}\nstate: {}", output, state);
+        }
+
+        if let Some(mtu) = &self.mtu {
+            output = format!("{}\nmtu: {}", output, mtu);
+        }
+
+        if let Some(master) = &self.master {
+            output = format!("{}\nmaster: {}", output, master);
+        }
+
+        if let Some(parent) = &self.parent {
+            output = format!("{}\nparent: {}", output, parent);
+        }
+
         if let Some(inet) = &self.inet {
             output = format!("{}\ninet: {}", output, inet);
         }
@@ -41,103 +252,64 @@ impl Display for Network {
             output = format!("{}\nmac: {}", output, mac);
         }
 
+        for address in self.inet_addresses.iter().skip(1) {
+            output = format!("{}\ninet: {}", output, address);
+        }
+
+        for address in &self.inet6_addresses {
+            output = format!("{}\ninet6: {}", output, address);
+        }
+
+        if let Some(stats) = &self.stats {
+            output = format!(
+                "{}\nrx: {} packets {} bytes\ntx: {} packets {} bytes",
+                output, stats.rx_packets, stats.rx_bytes, stats.tx_packets, stats.tx_bytes
+            );
+        }
+
         write!(f, "{}", output)
     }
 }
 
-/// Internal method to get the output of `ifconfig` split into
-/// a vector of strings for each network interface.
+/// # Try Get Networks
 /// 
-/// # Panics
+/// The fallible version of `get_networks`.
+/// Instead of returning an empty vector when the networks
+/// can not be read, this returns the reason as a `NetworkError`.
 /// 
-/// This method panics if `ifconfig` fails to execute.
-fn get_ifconfig_text() -> Vec<String> {
-    let output = std::process::Command::new("ifconfig")
-        .output()
-        .expect("Failed to execute ifconfig.");
-    let ifconfig_text = String::from_utf8_lossy(&output.stdout).to_string();
-
-    ifconfig_text
-        .split("\n\n")
-        .map(|x| x.to_string())
-        .collect::<Vec<String>>()
+/// # Returns
+/// 
+/// `Result<Vec<Network>, NetworkError>`: A vector of Network structs
+/// or the error that stopped them from being read.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::try_get_networks;
+/// 
+/// match try_get_networks() {
+///     Ok(networks) => networks.iter().for_each(|network| println!("{}", network)),
+///     Err(error) => eprintln!("Failed to get networks: {}", error),
+/// }
+/// ```
+pub fn try_get_networks() -> Result<Vec<Network>, NetworkError> {
+    backend::auto_networks()
 }
 
-/// # Parse Network
-/// Parses a string of text from `ifconfig` into a Network struct.
-/// This method is mostly always used internally, but can also be
-/// used externally if you have a string of text from `ifconfig`.
-/// This is not recommended, as it is easier to use the `get_networks`,
-/// however, it is possible.
+/// # Try Get Networks With
 /// 
-/// The parsing is done using a simple split algorithm, so don't expect
-/// it to be perfect.
-/// However, it should work for most cases.
+/// `try_get_networks`, run against the given backend.
 /// 
 /// # Arguments
 /// 
-/// * `line`: The string of text from `ifconfig` to parse.
+/// * `backend`: The backend to read the networks from.
 /// 
 /// # Returns
 /// 
-/// `Network`: A Network struct.
-/// 
-/// # Example
-/// ```
-/// use ip_extractor::parse_network;
-/// 
-/// let network = parse_network("wlp2s0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500
-///        inet
-///       inet6
-///      ether
-///     ");
-/// 
-/// println!("{}", network);
-/// ```
-pub fn parse_network(line: &str) -> Network {
-    let mut network = Network {
-        name: "".to_string(),
-        inet: None,
-        broadcast: None,
-        netmask: None,
-        mac: None,
-    };
-
-    network.name = line.split(':').collect::<Vec<&str>>()[0].to_string();
-
-    if let Some(inet) = line.split("inet ").collect::<Vec<&str>>().get(1) {
-        let mut inet = inet.to_string();
-        inet = inet.split(' ').collect::<Vec<&str>>()[0]
-            .to_string()
-            .replace('\n', "");
-        network.inet = Some(inet);
-    }
-
-    if let Some(broadcast) = line.split("broadcast ").collect::<Vec<&str>>().get(1) {
-        let mut broadcast = broadcast.to_string();
-        broadcast = broadcast.split(' ').collect::<Vec<&str>>()[0]
-            .to_string()
-            .replace('\n', "");
-        network.broadcast = Some(broadcast);
-    }
-
-    if let Some(netmask) = line.split("netmask ").collect::<Vec<&str>>().get(1) {
-        let mut netmask = netmask.to_string();
-        netmask = netmask.split(' ').collect::<Vec<&str>>()[0]
-            .to_string()
-            .replace('\n', "");
-        network.netmask = Some(netmask);
-    }
-
-    if let Some(mac) = line.split("ether ").collect::<Vec<&str>>().get(1) {
-        let mut mac = mac.to_string();
-        mac = mac.split(' ').collect::<Vec<&str>>()[0]
-            .to_string()
-            .replace('\n', "");
-        network.mac = Some(mac);
-    }
-
-    network
+/// `Result<Vec<Network>, NetworkError>`: A vector of Network structs
+/// or the error returned by the backend.
+pub fn try_get_networks_with(backend: &dyn Backend) -> Result<Vec<Network>, NetworkError> {
+    backend.networks()
 }
 
 /// # Get Networks
@@ -150,7 +322,15 @@ pub fn parse_network(line: &str) -> Network {
 /// filter on the returned vector.
 ///  
 /// This is the base for all other methods in this crate.
-/// The parsing is done by the `parse_network` method.
+/// The networks are read from the best backend available, see
+/// `detect_backend`. On Linux the interfaces are read directly
+/// from the kernel over netlink, so `ifconfig` does not need to
+/// be installed. If netlink is not available, or on other systems,
+/// it falls back to running `ifconfig` and parsing the output with
+/// the `parse_network` method.
+/// 
+/// If the networks can not be read at all, an empty vector
+/// is returned. Use `try_get_networks` to find out why.
 /// 
 /// # Returns
 /// 
@@ -168,20 +348,111 @@ pub fn parse_network(line: &str) -> Network {
 /// }
 /// ```
 pub fn get_networks() -> Vec<Network> {
-    let mut networks = Vec::new();
+    try_get_networks().unwrap_or_default()
+}
 
-    get_ifconfig_text().iter().filter(|x| !x.is_empty()).for_each(|x| {
-        networks.push(parse_network(x));
-    });
+/// # Get Networks With
+/// 
+/// `get_networks`, run against the given backend.
+/// This is useful to force a specific source, or to feed fake
+/// networks in tests with a `FixtureBackend`.
+/// 
+/// # Arguments
+/// 
+/// * `backend`: The backend to read the networks from.
+/// 
+/// # Returns
+/// 
+/// `Vec<Network>`: A vector of Network structs.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::{get_networks_with, IfconfigBackend};
+/// 
+/// for network in get_networks_with(&IfconfigBackend) {
+///     println!("{}", network);
+/// }
+/// ```
+pub fn get_networks_with(backend: &dyn Backend) -> Vec<Network> {
+    backend.networks().unwrap_or_default()
+}
 
-    networks
+/// # Get All Networks
+/// 
+/// Like `get_networks`, but also returns the network interfaces
+/// that are down or have no address, the same ones `ifconfig -a`
+/// shows, like an Ethernet card without a cable plugged in.
+/// Use `is_up` for the administrative state of each of them and
+/// `oper_state` for the operational one.
+/// 
+/// If the networks can not be read at all, an empty vector
+/// is returned. Use `try_get_all_networks` to find out why.
+/// 
+/// # Returns
+/// 
+/// `Vec<Network>`: A vector of Network structs.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::{get_all_networks, OperState};
+/// 
+/// for network in get_all_networks() {
+///     if network.is_up() && network.oper_state() == OperState::Down {
+///         println!("{} has no carrier", network.name);
+///     }
+/// }
+/// ```
+pub fn get_all_networks() -> Vec<Network> {
+    try_get_all_networks().unwrap_or_default()
+}
+
+/// # Try Get All Networks
+/// 
+/// The fallible version of `get_all_networks`.
+/// 
+/// # Returns
+/// 
+/// `Result<Vec<Network>, NetworkError>`: A vector of Network structs
+/// or the error that stopped them from being read.
+pub fn try_get_all_networks() -> Result<Vec<Network>, NetworkError> {
+    backend::auto_all_networks()
+}
+
+/// # Get All Networks With
+/// 
+/// `get_all_networks`, run against the given backend.
+/// 
+/// # Arguments
+/// 
+/// * `backend`: The backend to read the networks from.
+/// 
+/// # Returns
+/// 
+/// `Vec<Network>`: A vector of Network structs.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::{get_all_networks_with, SysfsBackend};
+/// 
+/// let backend = SysfsBackend::with_root("tests/fixtures/sysfs");
+/// 
+/// assert!(get_all_networks_with(&backend).iter().any(|x| !x.is_up()));
+/// ```
+pub fn get_all_networks_with(backend: &dyn Backend) -> Vec<Network> {
+    backend.all_networks().unwrap_or_default()
 }
 
 /// # Find Network
 /// 
 /// A method to find a specific network interface.
 /// This method is basically a iterator filter on the
-/// `get_networks` method.
+/// `get_networks` method for the names that contain the
+/// given one. If several do, the best match is returned,
+/// so `eth` finds `eth0` rather than `veth1a2b`, see
+/// `find_networks` for how they are ranked.
 /// 
 /// # Arguments
 /// 
@@ -204,18 +475,479 @@ pub fn get_networks() -> Vec<Network> {
 /// }
 /// ```
 pub fn find_network(name: &str) -> Option<Network> {
-    get_networks().iter().find(|x| x.name.contains(name)).cloned()
+    find_in(get_networks(), name)
 }
 
-/// # Get WLAN
+/// # Find Network With
+/// 
+/// `find_network`, run against the given backend.
+/// 
+/// # Arguments
+/// 
+/// * `backend`: The backend to read the networks from.
+/// * `name`: The name of the network interface to find.
+/// 
+/// # Returns
+/// 
+/// `Option<Network>`: An optional Network struct.
+pub fn find_network_with(backend: &dyn Backend, name: &str) -> Option<Network> {
+    find_in(get_networks_with(backend), name)
+}
+
+/// # Try Find Network
+/// 
+/// The fallible version of `find_network`.
+/// 
+/// # Arguments
+/// 
+/// * `name`: The name of the network interface to find.
+/// 
+/// # Returns
+/// 
+/// `Result<Option<Network>, NetworkError>`: An optional Network struct
+/// or the error that stopped the networks from being read.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::try_find_network;
+/// 
+/// match try_find_network("eth") {
+///     Ok(Some(network)) => println!("{}", network),
+///     Ok(None) => println!("No network found."),
+///     Err(error) => eprintln!("Failed to get networks: {}", error),
+/// }
+/// ```
+pub fn try_find_network(name: &str) -> Result<Option<Network>, NetworkError> {
+    Ok(find_in(try_get_networks()?, name))
+}
+
+fn find_in(networks: Vec<Network>, name: &str) -> Option<Network> {
+    query::find_matches(networks, name, MatchMode::Contains, false)
+        .into_iter()
+        .next()
+}
+
+/// # Find Network By
+/// 
+/// Like `find_network`, but the name is matched the given way,
+/// like exactly or as a glob, instead of anywhere in the name.
+/// 
+/// # Arguments
+/// 
+/// * `name`: The name, or the part of it, to find.
+/// * `mode`: How to match it, like `MatchMode::Exact`.
+/// * `ignore_case`: Whether to ignore the case of the letters.
+/// 
+/// # Returns
 /// 
-/// A method to get all wireless networks on the system.
-/// Most unix based systems use `wlan` or `wlp` as the
-/// prefix for wireless network interfaces.
+/// `Option<Network>`: The best match, if any interface matches.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::{find_network_by, MatchMode};
+/// 
+/// if let Some(network) = find_network_by("eth0", MatchMode::Exact, false) {
+///     println!("{}", network);
+/// }
+/// ```
+pub fn find_network_by(name: &str, mode: MatchMode, ignore_case: bool) -> Option<Network> {
+    find_networks(name, mode, ignore_case).into_iter().next()
+}
+
+/// # Find Network By With
+/// 
+/// `find_network_by`, run against the given backend.
+/// 
+/// # Arguments
+/// 
+/// * `backend`: The backend to read the networks from.
+/// * `name`: The name, or the part of it, to find.
+/// * `mode`: How to match it, like `MatchMode::Exact`.
+/// * `ignore_case`: Whether to ignore the case of the letters.
+/// 
+/// # Returns
+/// 
+/// `Option<Network>`: The best match, if any interface matches.
+pub fn find_network_by_with(
+    backend: &dyn Backend,
+    name: &str,
+    mode: MatchMode,
+    ignore_case: bool,
+) -> Option<Network> {
+    find_networks_with(backend, name, mode, ignore_case).into_iter().next()
+}
+
+/// # Find Networks
+/// 
+/// A method to find every network interface whose name matches,
+/// instead of only the first one. They are ranked best match
+/// first: a name that is the given one, then, when matching by
+/// prefix or anywhere in the name, the names that start with it,
+/// then the shorter names. Interfaces that are still tied
+/// are ordered by their index and name, so the result does not
+/// depend on the order the source lists them in.
+/// 
+/// # Arguments
+/// 
+/// * `name`: The name, or the part of it, to find.
+/// * `mode`: How to match it, like `MatchMode::Prefix`.
+/// * `ignore_case`: Whether to ignore the case of the letters.
+/// 
+/// # Returns
+/// 
+/// `Vec<Network>`: The matching networks, best match first.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::{find_networks, MatchMode};
+/// 
+/// for network in find_networks("en*", MatchMode::Glob, false) {
+///     println!("{}", network);
+/// }
+/// ```
+pub fn find_networks(name: &str, mode: MatchMode, ignore_case: bool) -> Vec<Network> {
+    try_find_networks(name, mode, ignore_case).unwrap_or_default()
+}
+
+/// # Find Networks With
+/// 
+/// `find_networks`, run against the given backend.
+/// 
+/// # Arguments
+/// 
+/// * `backend`: The backend to read the networks from.
+/// * `name`: The name, or the part of it, to find.
+/// * `mode`: How to match it, like `MatchMode::Prefix`.
+/// * `ignore_case`: Whether to ignore the case of the letters.
+/// 
+/// # Returns
+/// 
+/// `Vec<Network>`: The matching networks, best match first.
+pub fn find_networks_with(
+    backend: &dyn Backend,
+    name: &str,
+    mode: MatchMode,
+    ignore_case: bool,
+) -> Vec<Network> {
+    query::find_matches(get_networks_with(backend), name, mode, ignore_case)
+}
+
+/// # Try Find Networks
+/// 
+/// The fallible version of `find_networks`.
+/// 
+/// # Arguments
+/// 
+/// * `name`: The name, or the part of it, to find.
+/// * `mode`: How to match it, like `MatchMode::Prefix`.
+/// * `ignore_case`: Whether to ignore the case of the letters.
+/// 
+/// # Returns
+/// 
+/// `Result<Vec<Network>, NetworkError>`: The matching networks, best
+/// match first, or the error that stopped them from being read.
+pub fn try_find_networks(
+    name: &str,
+    mode: MatchMode,
+    ignore_case: bool,
+) -> Result<Vec<Network>, NetworkError> {
+    Ok(query::find_matches(try_get_networks()?, name, mode, ignore_case))
+}
+
+/// # Find By Ip
+/// 
+/// A method to find the network interface an IP address belongs
+/// to, like one from a log line. Every IPv4 and IPv6 address of
+/// every interface is checked, not only the primary one.
+/// 
+/// # Arguments
+/// 
+/// * `address`: The IPv4 or IPv6 address to look for.
+/// 
+/// # Returns
+/// 
+/// `Option<Network>`: The network with the address, if any. If
+/// several have it, the one with the lowest index is returned.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::find_by_ip;
+/// 
+/// if let Some(network) = find_by_ip("127.0.0.1".parse().unwrap()) {
+///     println!("127.0.0.1 is on {}", network.name);
+/// }
+/// ```
+pub fn find_by_ip(address: IpAddr) -> Option<Network> {
+    find_ip_in(get_networks(), address)
+}
+
+/// # Find By Ip With
+/// 
+/// `find_by_ip`, run against the given backend.
+/// 
+/// # Arguments
+/// 
+/// * `backend`: The backend to read the networks from.
+/// * `address`: The IPv4 or IPv6 address to look for.
+/// 
+/// # Returns
+/// 
+/// `Option<Network>`: The network with the address, if any.
+pub fn find_by_ip_with(backend: &dyn Backend, address: IpAddr) -> Option<Network> {
+    find_ip_in(get_networks_with(backend), address)
+}
+
+fn find_ip_in(networks: Vec<Network>, address: IpAddr) -> Option<Network> {
+    lowest_index(networks, |x| query::addresses(x).contains(&address))
+}
+
+/// # Find By Mac
+/// 
+/// A method to find the network interface with a MAC address,
+/// like one from the table of a switch.
+/// 
+/// # Arguments
+/// 
+/// * `mac`: The MAC address to look for.
+/// 
+/// # Returns
+/// 
+/// `Option<Network>`: The network with the MAC address, if any.
+/// A bridge or a VLAN often has the MAC address of the port it
+/// sits on, in which case the one with the lowest index, which
+/// is usually the port, is returned.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::{find_by_mac, MacAddr};
+/// 
+/// let mac: MacAddr = "52:54:00:12:34:56".parse().unwrap();
+/// 
+/// match find_by_mac(mac) {
+///     Some(network) => println!("{} is {}", mac, network.name),
+///     None => println!("{} is not on this machine", mac),
+/// }
+/// ```
+pub fn find_by_mac(mac: MacAddr) -> Option<Network> {
+    lowest_index(get_networks(), |x| x.mac_addr() == Some(mac))
+}
+
+/// # Find By Mac With
+/// 
+/// `find_by_mac`, run against the given backend.
+/// 
+/// # Arguments
+/// 
+/// * `backend`: The backend to read the networks from.
+/// * `mac`: The MAC address to look for.
+/// 
+/// # Returns
+/// 
+/// `Option<Network>`: The network with the MAC address, if any.
+pub fn find_by_mac_with(backend: &dyn Backend, mac: MacAddr) -> Option<Network> {
+    lowest_index(get_networks_with(backend), |x| x.mac_addr() == Some(mac))
+}
+
+/// # Find By Index
+/// 
+/// A method to find the network interface with an index, like
+/// the `ifindex` of a netlink message or the `if5` of a veth peer.
+/// Sources that do not report the index, like `ifconfig`, never
+/// find anything.
+/// 
+/// # Arguments
+/// 
+/// * `index`: The index the kernel gave the interface.
+/// 
+/// # Returns
+/// 
+/// `Option<Network>`: The network with the index, if any.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::find_by_index;
+/// 
+/// if let Some(network) = find_by_index(1) {
+///     println!("The first interface is {}", network.name);
+/// }
+/// ```
+pub fn find_by_index(index: u32) -> Option<Network> {
+    lowest_index(get_networks(), |x| x.index == Some(index))
+}
+
+/// # Find By Index With
+/// 
+/// `find_by_index`, run against the given backend.
+/// 
+/// # Arguments
+/// 
+/// * `backend`: The backend to read the networks from.
+/// * `index`: The index the kernel gave the interface.
+/// 
+/// # Returns
+/// 
+/// `Option<Network>`: The network with the index, if any.
+pub fn find_by_index_with(backend: &dyn Backend, index: u32) -> Option<Network> {
+    lowest_index(get_networks_with(backend), |x| x.index == Some(index))
+}
+
+/// # Is Local Address
+/// 
+/// Whether an IP address belongs to one of the network interfaces
+/// of this system, checking all of their addresses.
+/// 
+/// # Arguments
+/// 
+/// * `address`: The IPv4 or IPv6 address to check.
+/// 
+/// # Returns
+/// 
+/// `bool`: Whether an interface has the address.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::is_local_address;
+/// 
+/// if !is_local_address("10.0.0.5".parse().unwrap()) {
+///     println!("10.0.0.5 is somewhere else");
+/// }
+/// ```
+pub fn is_local_address(address: IpAddr) -> bool {
+    find_by_ip(address).is_some()
+}
+
+/// # Is Local Address With
+/// 
+/// `is_local_address`, run against the given backend.
+/// 
+/// # Arguments
+/// 
+/// * `backend`: The backend to read the networks from.
+/// * `address`: The IPv4 or IPv6 address to check.
+/// 
+/// # Returns
+/// 
+/// `bool`: Whether an interface has the address.
+pub fn is_local_address_with(backend: &dyn Backend, address: IpAddr) -> bool {
+    find_by_ip_with(backend, address).is_some()
+}
+
+/// The first of the networks that matches, by their index.
+fn lowest_index(networks: Vec<Network>, matches: impl Fn(&Network) -> bool) -> Option<Network> {
+    networks
+        .into_iter()
+        .filter(|x| matches(x))
+        .min_by_key(|x| (x.index.is_none(), x.index))
+}
+
+/// # Get By Kind
+/// 
+/// A method to get all network interfaces of a kind, like
+/// the bridges or the WireGuard tunnels on the system.
 /// This method is basically a iterator filter on the
-/// `get_networks` method for finding them.
-/// You can also pass in an optional identifier to fuzzy
-/// match a wireless network interface's name.
+/// `get_networks` method for the networks whose `kind`
+/// is the given one. Where the source knows the kind,
+/// like netlink and sysfs on Linux, renamed interfaces are
+/// found too, otherwise it is guessed from their names.
+/// 
+/// # Arguments
+/// 
+/// * `kind`: The kind of network interface to get.
+/// * `identifier`: An optional identifier to fuzzy match
+/// * `unaddressed`: Whether to keep the interfaces without
+///   an IPv4 address.
+/// 
+/// # Returns
+/// 
+/// `Vec<Network>`: A vector of Network structs.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::{get_by_kind, InterfaceKind, Unaddressed};
+/// 
+/// for network in get_by_kind(InterfaceKind::Bridge, None, Unaddressed::Include) {
+///     println!("{}", network);
+/// }
+/// ```
+pub fn get_by_kind(
+    kind: InterfaceKind,
+    identifier: Option<&str>,
+    unaddressed: Unaddressed,
+) -> Vec<Network> {
+    filter_kind(get_networks(), kind, identifier, unaddressed)
+}
+
+/// # Get By Kind With
+/// 
+/// `get_by_kind`, run against the given backend.
+/// 
+/// # Arguments
+/// 
+/// * `backend`: The backend to read the networks from.
+/// * `kind`: The kind of network interface to get.
+/// * `identifier`: An optional identifier to fuzzy match
+/// * `unaddressed`: Whether to keep the interfaces without
+///   an IPv4 address.
+/// 
+/// # Returns
+/// 
+/// `Vec<Network>`: A vector of Network structs.
+/// 
+/// # Example
+/// 
+/// ```
+/// use ip_extractor::{get_by_kind_with, InterfaceKind, SysfsBackend, Unaddressed};
+/// 
+/// let backend = SysfsBackend::with_root("tests/fixtures/sysfs");
+/// let loopback = get_by_kind_with(&backend, InterfaceKind::Loopback, None, Unaddressed::Exclude);
+/// 
+/// assert_eq!(loopback[0].name, "lo");
+/// ```
+pub fn get_by_kind_with(
+    backend: &dyn Backend,
+    kind: InterfaceKind,
+    identifier: Option<&str>,
+    unaddressed: Unaddressed,
+) -> Vec<Network> {
+    filter_kind(get_networks_with(backend), kind, identifier, unaddressed)
+}
+
+fn filter_kind(
+    networks: Vec<Network>,
+    kind: InterfaceKind,
+    identifier: Option<&str>,
+    unaddressed: Unaddressed,
+) -> Vec<Network> {
+    networks
+        .into_iter()
+        .filter(|x| {
+            x.kind() == kind
+             && (x.inet.is_some() || unaddressed == Unaddressed::Include) &&
+            match identifier {
+                Some(ref identifier) => x.name.contains(identifier),
+                None => true,
+            }
+        })
+        .collect::<Vec<Network>>()
+}
+
+/// # Get WLAN
+/// 
+/// A method to get all wireless networks on the system
+/// that have an IPv4 address. This is `get_by_kind` for
+/// `InterfaceKind::Wireless`, so renamed interfaces are found
+/// where the source knows the kind, like netlink and sysfs on
+/// Linux, otherwise it is guessed from names like `wlan0` or
+/// `wlp2s0`. You can also pass in an optional identifier to
+/// fuzzy match a wireless network interface's name.
 /// 
 /// # Arguments
 /// 
@@ -243,24 +975,32 @@ pub fn find_network(name: &str) -> Option<Network> {
 /// }
 /// ```
 pub fn get_wlan(identifier: Option<&str>) -> Vec<Network> {
-    get_networks()
-        .iter()
-        .filter(|x| {
-            (x.name.contains("wlan") || x.name.contains("wlp"))
-             && x.inet.is_some() &&
-            match identifier {
-                Some(ref identifier) => x.name.contains(identifier),
-                None => true,
-            }
-        })
-        .cloned()
-        .collect::<Vec<Network>>()
+    get_by_kind(InterfaceKind::Wireless, identifier, Unaddressed::Exclude)
+}
+
+/// # Get WLAN With
+/// 
+/// `get_wlan`, run against the given backend.
+/// 
+/// # Arguments
+/// 
+/// * `backend`: The backend to read the networks from.
+/// * `identifier`: An optional identifier to fuzzy match
+/// 
+/// # Returns
+/// 
+/// `Vec<Network>`: A vector of Network structs.
+pub fn get_wlan_with(backend: &dyn Backend, identifier: Option<&str>) -> Vec<Network> {
+    get_by_kind_with(backend, InterfaceKind::Wireless, identifier, Unaddressed::Exclude)
 }
 
 /// # Get Ethernet
 /// 
 /// Similar to `get_wlan`, this method gets all ethernet
-/// network interfaces on the system.
+/// network interfaces on the system that have an IPv4 address.
+/// This is `get_by_kind` for `InterfaceKind::Ethernet`, so
+/// virtual interfaces that look like Ethernet, like `veth`
+/// pairs and bridges, are not included.
 /// 
 /// # Arguments
 /// 
@@ -294,23 +1034,29 @@ pub fn get_wlan(identifier: Option<&str>) -> Vec<Network> {
 /// ethernet network interfaces, so it may not work as
 /// expected.
 pub fn get_ethernet(identifier: Option<&str>) -> Vec<Network> {
-    get_networks()
-        .iter()
-        .filter(|x| {
-            (x.name.contains("eth") || x.name.contains("enp"))
-             && x.inet.is_some() &&
-            match identifier {
-                Some(ref identifier) => x.name.contains(identifier),
-                None => true,
-            }
-        })
-        .cloned()
-        .collect::<Vec<Network>>()
+    get_by_kind(InterfaceKind::Ethernet, identifier, Unaddressed::Exclude)
+}
+
+/// # Get Ethernet With
+/// 
+/// `get_ethernet`, run against the given backend.
+/// 
+/// # Arguments
+/// 
+/// * `backend`: The backend to read the networks from.
+/// * `identifier`: An optional identifier to fuzzy match
+/// 
+/// # Returns
+/// 
+/// `Vec<Network>`: A vector of Network structs.
+pub fn get_ethernet_with(backend: &dyn Backend, identifier: Option<&str>) -> Vec<Network> {
+    get_by_kind_with(backend, InterfaceKind::Ethernet, identifier, Unaddressed::Exclude)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::fixture::network;
 
     #[test]
     fn does_get_networks_work() {
@@ -318,6 +1064,254 @@ mod tests {
         assert!(!networks.is_empty());
     }
 
+    #[test]
+    fn does_try_get_networks_work() {
+        let networks = try_get_networks().unwrap();
+        assert!(!networks.is_empty());
+    }
+
+    #[test]
+    fn does_parse_network_find_all_inet_addresses() {
+        let network = parse_network(
+            "eth0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500
+        inet 10.0.0.5  netmask 255.255.255.0  broadcast 10.0.0.255
+        inet 10.0.1.7  netmask 255.255.0.0  broadcast 10.0.255.255
+        ether 52:54:00:12:34:56  txqueuelen 1000  (Ethernet)
+",
+        );
+
+        assert_eq!(network.inet.as_deref(), Some("10.0.0.5"));
+        assert_eq!(network.netmask.as_deref(), Some("255.255.255.0"));
+        assert_eq!(network.broadcast.as_deref(), Some("10.0.0.255"));
+        assert_eq!(network.inet_addresses.len(), 2);
+        assert_eq!(network.inet_addresses[1].address.to_string(), "10.0.1.7");
+        assert_eq!(network.inet_addresses[1].prefix_len, 16);
+        assert_eq!(
+            network.inet_addresses[1].broadcast.map(|x| x.to_string()),
+            Some("10.0.255.255".to_string())
+        );
+    }
+
+    #[test]
+    fn does_parse_network_find_inet6_addresses() {
+        let network = parse_network(
+            "eth0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500
+        inet 192.0.2.2  netmask 255.255.255.0  broadcast 192.0.2.255
+        inet6 fe80::fc:ff:fe00:1  prefixlen 64  scopeid 0x20<link>
+        inet6 2001:db8::2  prefixlen 64  scopeid 0x0<global>
+        ether 02:fc:00:00:00:01  txqueuelen 1000  (Ethernet)
+",
+        );
+
+        assert_eq!(network.inet6_addresses.len(), 2);
+        assert_eq!(network.inet6_addresses[0].scope, Scope::Link);
+        assert_eq!(
+            network.inet6_addresses[1].address.to_string(),
+            "2001:db8::2"
+        );
+        assert_eq!(network.inet6_addresses[1].prefix_len, 64);
+        assert_eq!(network.inet6_addresses[1].scope, Scope::Global);
+        assert!(network
+            .to_string()
+            .contains("inet6: fe80::fc:ff:fe00:1/64 scope link"));
+    }
+
+    #[test]
+    fn does_typed_accessors_work() {
+        let network = parse_network(
+            "eth0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500
+        inet 10.0.0.5  netmask 255.255.255.0  broadcast 10.0.0.255
+        inet6 fe80::1  prefixlen 64  scopeid 0x20<link>
+        ether 52:54:00:12:34:56  txqueuelen 1000  (Ethernet)
+",
+        );
+
+        assert_eq!(network.inet_addr(), Some(Ipv4Addr::new(10, 0, 0, 5)));
+        assert_eq!(network.broadcast_addr(), Some(Ipv4Addr::new(10, 0, 0, 255)));
+        assert_eq!(network.netmask_addr().map(|x| x.prefix_len()), Some(24));
+        assert_eq!(
+            network.mac_addr().map(|x| x.to_cisco_string()).as_deref(),
+            Some("5254.0012.3456")
+        );
+        assert_eq!(network.ip_addrs().len(), 2);
+
+        let network = Network {
+            inet: Some("".to_string()),
+            mac: Some("garbage".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(network.inet_addr(), None);
+        assert_eq!(network.mac_addr(), None);
+    }
+
+    #[test]
+    fn does_parse_network_find_flags_and_mtu() {
+        let network = parse_network(
+            "lo: flags=73<UP,LOOPBACK,RUNNING>  mtu 65536
+        inet 127.0.0.1  netmask 255.0.0.0
+",
+        );
+
+        assert_eq!(network.raw_flags, Some(73));
+        assert_eq!(network.flags.bits(), 73);
+        assert_eq!(network.mtu, Some(65536));
+        assert!(network.is_up());
+        assert!(network.is_running());
+        assert!(network.is_loopback());
+        assert!(!network.is_point_to_point());
+    }
+
+    #[test]
+    fn does_parse_network_find_stats() {
+        let network = parse_network(
+            "eth0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1400
+        inet 192.0.2.2  netmask 255.255.255.0  broadcast 192.0.2.255
+        ether 02:fc:00:00:00:01  txqueuelen 1000  (Ethernet)
+        RX packets 99  bytes 6432 (6.2 KiB)
+        RX errors 0  dropped 3  overruns 0  frame 0
+        TX packets 98  bytes 8809 (8.6 KiB)
+        TX errors 0  dropped 0 overruns 0  carrier 1  collisions 0
+",
+        );
+
+        let stats = network.stats.unwrap();
+        assert_eq!(stats.rx_packets, 99);
+        assert_eq!(stats.rx_bytes, 6432);
+        assert_eq!(stats.rx_dropped, 3);
+        assert_eq!(stats.tx_bytes, 8809);
+        assert_eq!(stats.tx_carrier, 1);
+        assert_eq!(parse_network("lo: flags=73<UP,LOOPBACK,RUNNING>").stats, None);
+    }
+
+    #[test]
+    fn does_backend_injection_work() {
+        let backend = FixtureBackend::of([
+            network("veth1a2b"),
+            network("wlp2s0").inet("192.168.1.4"),
+            network("enp3s0").inet("10.0.0.5"),
+        ]);
+
+        assert_eq!(get_networks_with(&backend).len(), 3);
+        assert_eq!(find_network_with(&backend, "enp").unwrap().name, "enp3s0");
+        assert_eq!(get_wlan_with(&backend, None).len(), 1);
+        assert_eq!(get_ethernet_with(&backend, Some("enp3")).len(), 1);
+    }
+
+    #[test]
+    fn does_find_network_rank_matches() {
+        let backend = FixtureBackend::of([
+            network("veth1a2b").index(7),
+            network("eth10").index(5),
+            network("ethX").index(4),
+            network("eth0").index(2),
+            network("ETH1").index(3),
+        ]);
+        let names = |networks: Vec<Network>| {
+            networks.into_iter().map(|x| x.name).collect::<Vec<String>>()
+        };
+
+        assert_eq!(find_network_with(&backend, "eth").unwrap().name, "eth0");
+        assert_eq!(
+            names(find_networks_with(&backend, "eth", MatchMode::Contains, false)),
+            ["eth0", "ethX", "eth10", "veth1a2b"]
+        );
+        assert_eq!(
+            names(find_networks_with(&backend, "eth", MatchMode::Prefix, true)),
+            ["eth0", "ETH1", "ethX", "eth10"]
+        );
+        assert_eq!(
+            names(find_networks_with(&backend, "eth?", MatchMode::Glob, false)),
+            ["eth0", "ethX"]
+        );
+        assert_eq!(
+            names(find_networks_with(&backend, "0", MatchMode::Suffix, false)),
+            ["eth0", "eth10"]
+        );
+        assert_eq!(
+            find_network_by_with(&backend, "eth1", MatchMode::Exact, true).unwrap().name,
+            "ETH1"
+        );
+        assert!(find_network_by_with(&backend, "eth", MatchMode::Exact, false).is_none());
+    }
+
+    #[test]
+    fn does_reverse_lookup_work() {
+        let backend = FixtureBackend::new(parse_ip_text(include_str!(
+            "../tests/fixtures/ip-addr.txt"
+        )));
+        let mac: MacAddr = "52:54:00:12:34:56".parse().unwrap();
+
+        // 192.168.1.21 is the secondary address of br0, not its inet.
+        let br0 = find_by_ip_with(&backend, "192.168.1.21".parse().unwrap()).unwrap();
+        assert_eq!(br0.name, "br0");
+        assert_eq!(
+            find_by_ip_with(&backend, "fe80::5054:ff:feab:cdef".parse().unwrap()).unwrap().index,
+            br0.index
+        );
+        assert_eq!(find_by_mac_with(&backend, mac).unwrap().name, "enp3s0");
+        // The VLAN has the MAC address of the bridge it sits on.
+        let mac: MacAddr = "52:54:00:ab:cd:ef".parse().unwrap();
+        assert_eq!(find_by_mac_with(&backend, mac).unwrap().name, "br0");
+        assert_eq!(find_by_index_with(&backend, 1).unwrap().name, "lo");
+        assert!(find_by_index_with(&backend, 99).is_none());
+        assert!(is_local_address_with(&backend, "127.0.0.1".parse().unwrap()));
+        assert!(!is_local_address_with(&backend, "192.168.1.22".parse().unwrap()));
+    }
+
+    #[test]
+    fn does_kind_work() {
+        let backend = FixtureBackend::of([
+            network("veth1a2b").inet("172.17.0.1"),
+            network("ens18").inet("10.0.0.5"),
+            network("wlx00c0ca123456").inet("192.168.1.4"),
+            network("uplink")
+                .inet("192.168.2.7")
+                .link_kind(InterfaceKind::Wireless),
+            network("eth1")
+                .inet("10.1.0.1")
+                .link_kind(InterfaceKind::Bridge),
+        ]);
+
+        let wlan = get_wlan_with(&backend, None);
+        let names: Vec<&str> = wlan.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, ["wlx00c0ca123456", "uplink"]);
+
+        let ethernet = get_ethernet_with(&backend, None);
+        let names: Vec<&str> = ethernet.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, ["ens18"]);
+    }
+
+    #[test]
+    fn does_get_by_kind_work() {
+        let backend = FixtureBackend::of([
+            network("br0").inet("192.168.1.20"),
+            network("br-lan"),
+            network("wg0").inet("10.100.0.2"),
+        ]);
+
+        let bridges = get_by_kind_with(&backend, InterfaceKind::Bridge, None, Unaddressed::Exclude);
+        assert_eq!(bridges.len(), 1);
+
+        let bridges = get_by_kind_with(&backend, InterfaceKind::Bridge, None, Unaddressed::Include);
+        assert_eq!(bridges.len(), 2);
+
+        let bridges = get_by_kind_with(
+            &backend,
+            InterfaceKind::Bridge,
+            Some("lan"),
+            Unaddressed::Include,
+        );
+        assert_eq!(bridges[0].name, "br-lan");
+
+        let tunnels = get_by_kind_with(
+            &backend,
+            InterfaceKind::WireGuard,
+            None,
+            Unaddressed::default(),
+        );
+        assert_eq!(tunnels[0].name, "wg0");
+    }
+
     #[test]
     fn does_wlan_work(){
         let wlan = get_wlan(None);
@@ -0,0 +1,155 @@
+//! # Diagnostic
+//! Describes what was wrong with the text a parser was given,
+//! so a change in the output of a tool can be found and reported.
+
+use std::fmt::Display;
+
+/// # Diagnostic
+/// Something in the text of an interface that is not what the parser
+/// expected, like an `inet` line without an address after it.
+/// * line: The line it is on, counted from 1.
+/// * column: The column the offending text starts at, counted from 1.
+/// * expected: What the parser expected to find there.
+/// * found: The text that was found instead, empty at the end of a line.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::parse_network_lenient;
+///
+/// let (_, warnings) = parse_network_lenient("eth0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500
+///         inet 10.0.0.300  netmask 255.255.255.0
+/// ");
+///
+/// assert_eq!(warnings[0].line, 2);
+/// assert_eq!(warnings[0].column, 14);
+/// assert_eq!(warnings[0].found, "10.0.0.300");
+/// assert_eq!(
+///     warnings[0].to_string(),
+///     "line 2, column 14: expected an IPv4 address, found `10.0.0.300`"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, ",
+            self.line, self.column, self.expected
+        )?;
+        match self.found.is_empty() {
+            true => write!(f, "found the end of the line"),
+            false => write!(f, "found `{}`", self.found),
+        }
+    }
+}
+
+/// The tokens of a line, split on whitespace, with the column each
+/// of them starts at, counted in characters from 1.
+pub(crate) fn columns(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (column, (offset, x)) in line.char_indices().enumerate() {
+        match (start, x.is_whitespace()) {
+            (None, false) => start = Some((column, offset)),
+            (Some((column, begin)), true) => {
+                tokens.push((column + 1, &line[begin..offset]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((column, begin)) = start {
+        tokens.push((column + 1, &line[begin..]));
+    }
+
+    tokens
+}
+
+/// Collects the diagnostics of the lines of a text.
+pub(crate) struct Diagnostics {
+    pub(crate) list: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub(crate) fn new() -> Diagnostics {
+        Diagnostics { list: Vec::new() }
+    }
+
+    /// Reports that something else was expected on a line.
+    /// * `token`: The offending token with its column, or None
+    ///   if the line ended before it.
+    pub(crate) fn push(
+        &mut self,
+        number: usize,
+        line: &str,
+        token: Option<(usize, &str)>,
+        expected: &str,
+    ) {
+        let (column, found) = token.unwrap_or((line.chars().count() + 1, ""));
+
+        self.list.push(Diagnostic {
+            line: number,
+            column,
+            expected: expected.to_string(),
+            found: found.to_string(),
+        });
+    }
+
+    /// Checks that a token is there and is valid, and reports it if not.
+    pub(crate) fn check(
+        &mut self,
+        number: usize,
+        line: &str,
+        token: Option<(usize, &str)>,
+        expected: &str,
+        valid: impl Fn(&str) -> bool,
+    ) {
+        match token {
+            Some((_, x)) if valid(x) => {}
+            token => self.push(number, line, token, expected),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_columns_work() {
+        assert_eq!(
+            columns("  inet 10.0.0.5  netmask"),
+            vec![(3, "inet"), (8, "10.0.0.5"), (18, "netmask")]
+        );
+        assert!(columns("   ").is_empty());
+    }
+
+    #[test]
+    fn does_diagnostics_work() {
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.check(2, "    inet", None, "an IPv4 address", |_| true);
+        diagnostics.check(3, "mtu x", Some((5, "x")), "a number", |x| {
+            x.parse::<u32>().is_ok()
+        });
+        diagnostics.check(4, "mtu 1500", Some((5, "1500")), "a number", |x| {
+            x.parse::<u32>().is_ok()
+        });
+
+        assert_eq!(diagnostics.list.len(), 2);
+        assert_eq!(diagnostics.list[0].column, 9);
+        assert_eq!(
+            diagnostics.list[0].to_string(),
+            "line 2, column 9: expected an IPv4 address, found the end of the line"
+        );
+        assert_eq!(diagnostics.list[1].found, "x");
+    }
+}
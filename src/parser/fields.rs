@@ -0,0 +1,211 @@
+//! # Fields
+//! Collects the key/value pairs of a line that a parser does not
+//! know, so they end up in `Network::extra` instead of being lost.
+
+use std::collections::BTreeMap;
+
+/// The number of values of a known key that takes the rest of the line.
+pub(crate) const REST: usize = usize::MAX;
+
+/// Gets the number of values of a key from a list of known keys.
+pub(crate) fn lookup(known: &[(&str, usize)], key: &str) -> Option<usize> {
+    known
+        .iter()
+        .find(|(x, _)| *x == key)
+        .map(|(_, values)| *values)
+}
+
+/// Whether a token looks like the name of a key, like `txqueuelen`,
+/// rather than a value, like `1000` or `0xf7100000`.
+fn is_key(token: &str) -> bool {
+    token.starts_with(|x: char| x.is_ascii_alphabetic())
+        && token
+            .chars()
+            .all(|x| x.is_ascii_alphanumeric() || matches!(x, '_' | '-' | '/' | '.'))
+}
+
+/// Splits a `key=value` or `key:value` token, like `txqueuelen:1000`.
+/// The key has to be longer than two letters, so a MAC address like
+/// `de:ad:be:ef:00:01` is not taken for one.
+fn split_key(token: &str) -> Option<(&str, &str)> {
+    let (key, value) = token.split_once('=').or_else(|| token.split_once(':'))?;
+
+    if key.len() > 2 && is_key(key) && !value.is_empty() {
+        Some((key, value))
+    } else {
+        None
+    }
+}
+
+/// Internal method to collect the unknown fields of a line of
+/// `ifconfig`, where a key can be followed by a value or stand on
+/// its own, like `device interrupt 16 memory 0xf7100000-f7120000`.
+/// * `known`: Gets the number of values of a key the parser knows,
+///   or None if it does not know the key.
+///
+/// A value is a token that does not look like a key, a key that
+/// ends in a colon takes the rest of the line, like `status: active`,
+/// and text in brackets that does not belong to a known key, like
+/// `(Ethernet)`, is kept as the `description`.
+pub(crate) fn unknown_fields(
+    line: &str,
+    known: impl Fn(&str) -> Option<usize>,
+    extra: &mut BTreeMap<String, String>,
+) {
+    let tokens = line.split_whitespace().collect::<Vec<&str>>();
+    let mut after_known = false;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = tokens[i];
+
+        if token.starts_with('(') {
+            let end = (i..tokens.len())
+                .find(|x| tokens[*x].ends_with(')'))
+                .unwrap_or(tokens.len() - 1);
+            if !after_known {
+                let text = tokens[i..=end].join(" ");
+                extra.insert(
+                    "description".to_string(),
+                    text.trim_start_matches('(')
+                        .trim_end_matches(')')
+                        .to_string(),
+                );
+            }
+            i = end + 1;
+            continue;
+        }
+
+        if let Some(key) = token.strip_suffix(':').filter(|x| is_key(x)) {
+            if known(key).is_none() {
+                extra.insert(key.to_string(), tokens[i + 1..].join(" "));
+            }
+            return;
+        }
+
+        if let Some((key, value)) = split_key(token) {
+            after_known = known(key).is_some();
+            if !after_known {
+                extra.insert(key.to_string(), value.to_string());
+            }
+            i += 1;
+            continue;
+        }
+
+        match known(token) {
+            Some(REST) => return,
+            Some(values) => {
+                after_known = true;
+                i += 1 + values;
+            }
+            // A key of two words, like `Base address:0x2000`.
+            None if is_key(token) && tokens.get(i + 1).and_then(|x| split_key(x)).is_some() => {
+                after_known = false;
+                let (key, value) = split_key(tokens[i + 1]).unwrap_or_default();
+                extra.insert(format!("{} {}", token, key), value.to_string());
+                i += 2;
+            }
+            None if is_key(token) => {
+                after_known = false;
+                let value = tokens
+                    .get(i + 1)
+                    .filter(|x| !is_key(x) && !x.starts_with('('));
+                extra.insert(token.to_string(), value.unwrap_or(&"").to_string());
+                i += 1 + value.map_or(0, |_| 1);
+            }
+            // A value on its own, without a key.
+            None => i += 1,
+        }
+    }
+}
+
+/// Internal method to collect the unknown fields of a line of `ip`,
+/// where every key is followed by exactly one value, like
+/// `qdisc noqueue state UP group default qlen 1000`.
+/// * `known`: Gets the number of values of a key the parser knows,
+///   or None if it does not know the key.
+pub(crate) fn unknown_pairs(
+    line: &str,
+    known: impl Fn(&str) -> Option<usize>,
+    extra: &mut BTreeMap<String, String>,
+) {
+    let tokens = line.split_whitespace().collect::<Vec<&str>>();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match known(tokens[i]) {
+            Some(REST) => return,
+            Some(values) => i += 1 + values,
+            None => {
+                let value = tokens.get(i + 1).copied().unwrap_or_default();
+                extra.insert(tokens[i].to_string(), value.to_string());
+                i += 2;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(line: &str, known: &[(&str, usize)]) -> BTreeMap<String, String> {
+        let mut extra = BTreeMap::new();
+        unknown_fields(line, |key| lookup(known, key), &mut extra);
+        extra
+    }
+
+    #[test]
+    fn does_unknown_fields_work() {
+        let extra = fields(
+            "ether 52:54:00:12:34:56  txqueuelen 1000  (Ethernet)",
+            &[("ether", 1)],
+        );
+        assert_eq!(extra.len(), 2);
+        assert_eq!(extra["txqueuelen"], "1000");
+        assert_eq!(extra["description"], "Ethernet");
+
+        let extra = fields("device interrupt 16  memory 0xf7100000-f7120000", &[]);
+        assert_eq!(extra["device"], "");
+        assert_eq!(extra["interrupt"], "16");
+        assert_eq!(extra["memory"], "0xf7100000-f7120000");
+
+        let extra = fields(
+            "RX bytes:98765432 (94.1 MiB)  TX bytes:12345678 (11.7 MiB)",
+            &[("RX", 0), ("TX", 0), ("bytes", 1)],
+        );
+        assert!(extra.is_empty());
+
+        let extra = fields("collisions:0 txqueuelen:1000", &[("collisions", 1)]);
+        assert_eq!(extra.len(), 1);
+        assert_eq!(extra["txqueuelen"], "1000");
+
+        let extra = fields("Interrupt:19 Base address:0x2000", &[]);
+        assert_eq!(extra["Interrupt"], "19");
+        assert_eq!(extra["Base address"], "0x2000");
+
+        let extra = fields("media: Ethernet autoselect (1000baseT <full-duplex>)", &[]);
+        assert_eq!(
+            extra["media"],
+            "Ethernet autoselect (1000baseT <full-duplex>)"
+        );
+
+        let extra = fields("nd6 options=29<PERFORMNUD,IFDISABLED>", &[("nd6", REST)]);
+        assert!(extra.is_empty());
+    }
+
+    #[test]
+    fn does_unknown_pairs_work() {
+        let mut extra = BTreeMap::new();
+        unknown_pairs(
+            "mtu 1500 qdisc noqueue state UP group default qlen 1000",
+            |key| (key == "mtu").then_some(1),
+            &mut extra,
+        );
+
+        assert_eq!(extra.len(), 4);
+        assert_eq!(extra["qdisc"], "noqueue");
+        assert_eq!(extra["state"], "UP");
+        assert_eq!(extra["qlen"], "1000");
+    }
+}
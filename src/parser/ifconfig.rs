@@ -0,0 +1,1104 @@
+//! # Ifconfig Parser
+//! Turns the text printed by `ifconfig` into Network structs.
+//! There are a few dialects of it around, so the dialect is
+//! detected from the text before parsing it.
+
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use super::diagnostic::{columns, Diagnostic, Diagnostics};
+use super::fields::{lookup, unknown_fields, REST};
+use crate::{
+    AddressFlags, Inet6Address, InetAddress, InterfaceFlags, InterfaceStats, MacAddr, Netmask,
+    Network, NetworkError, OperState, Scope,
+};
+
+/// The dialects of `ifconfig` output.
+/// * NetTools: net-tools 2.x, like `eth0: flags=4163<UP,...>  mtu 1500`.
+/// * Legacy: net-tools 1.x and BusyBox, like `eth0      Link encap:Ethernet`.
+/// * Bsd: FreeBSD and macOS, like `en0: flags=8863<UP,...> mtu 1500`
+///   followed by `inet 10.0.0.5 netmask 0xffffff00`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Dialect {
+    NetTools,
+    Legacy,
+    Bsd,
+}
+
+impl Dialect {
+    /// Works out the dialect of the text of an interface.
+    fn detect(text: &str) -> Dialect {
+        let legacy = text.lines().any(|line| {
+            line.contains("Link encap:")
+                || line.trim_start().starts_with("inet addr:")
+                || line.split_whitespace().any(|x| x.starts_with("MTU:"))
+        });
+        if legacy {
+            return Dialect::Legacy;
+        }
+
+        // The header looks the same as net-tools 2.x, but the lines
+        // below it give BSD away.
+        let bsd = text.lines().skip(1).map(|x| x.trim()).any(|line| {
+            ["status:", "media:", "options=", "nd6 ", "groups:"]
+                .iter()
+                .any(|x| line.starts_with(x))
+                || line.contains("netmask 0x")
+                || (line.starts_with("inet6 ") && line.contains('%'))
+        });
+        if bsd {
+            Dialect::Bsd
+        } else {
+            Dialect::NetTools
+        }
+    }
+}
+
+/// Internal method to split the output of `ifconfig` into the text
+/// of each interface. A new interface starts at every line that is
+/// not indented, which works for every dialect, including BSD where
+/// interfaces are not separated by blank lines.
+/// The blocks are slices of the original text, including their
+/// line endings and any blank lines that follow them.
+pub(crate) fn split_interfaces(text: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let indented = line.starts_with(|x: char| x.is_whitespace());
+        if !indented && offset > start && !text[start..offset].trim().is_empty() {
+            blocks.push(&text[start..offset]);
+            start = offset;
+        }
+        offset += line.len();
+    }
+
+    if !text[start..].trim().is_empty() {
+        blocks.push(&text[start..]);
+    }
+
+    blocks
+}
+
+/// # Parse Network
+/// Parses a string of text from `ifconfig` into a Network struct.
+/// This method is mostly always used internally, but can also be
+/// used externally if you have a string of text from `ifconfig`.
+/// This is not recommended, as it is easier to use the `get_networks`,
+/// however, it is possible.
+///
+/// The parsing is done using a simple split algorithm, so don't expect
+/// it to be perfect.
+/// However, it should work for most cases.
+///
+/// Both the current net-tools output and the older one, that
+/// BusyBox still prints, are understood. The older one looks like
+/// `eth0      Link encap:Ethernet  HWaddr 00:0C:29:3E:5B:7A` followed
+/// by `inet addr:10.0.0.5  Bcast:10.0.0.255  Mask:255.255.255.0`.
+/// The FreeBSD and macOS output is understood too, and its hex
+/// netmasks, like `0xffffff00`, are converted to the dotted form.
+///
+/// # Arguments
+///
+/// * `line`: The string of text from `ifconfig` to parse.
+///
+/// # Returns
+///
+/// `Network`: A Network struct.
+///
+/// Text that does not look like `ifconfig` output still gives a
+/// Network, with whatever could be read from it. Use
+/// `parse_network_lenient` to also find out what was wrong with it,
+/// or `parse_network_strict` to get an error instead.
+///
+/// # Example
+/// ```
+/// use ip_extractor::parse_network;
+///
+/// let network = parse_network("wlp2s0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500
+///         inet 192.168.1.23  netmask 255.255.255.0  broadcast 192.168.1.255
+///         ether 3c:a9:f4:12:34:56  txqueuelen 1000  (Ethernet)
+/// ");
+///
+/// assert_eq!(network.name, "wlp2s0");
+/// assert_eq!(network.inet.as_deref(), Some("192.168.1.23"));
+/// println!("{}", network);
+/// ```
+pub fn parse_network(line: &str) -> Network {
+    let dialect = Dialect::detect(line);
+
+    let mut network = match dialect {
+        Dialect::NetTools => parse_net_tools(line),
+        Dialect::Legacy => parse_legacy(line),
+        Dialect::Bsd => parse_bsd(line),
+    };
+    parse_name(&mut network);
+    network.raw = Some(line.to_string());
+    network.extra = extra_fields(line, dialect);
+
+    network
+}
+
+/// # Parse Network Lenient
+/// Parses the text of an interface like `parse_network`, and also
+/// returns a Diagnostic for everything in it that is not what
+/// `ifconfig` prints, like an `inet` line without an address or a
+/// name that is not followed by a colon. The Network is the same one
+/// `parse_network` returns, so the warnings can be logged to find out
+/// when a system starts to print something new.
+///
+/// # Arguments
+///
+/// * `text`: The string of text from `ifconfig` to parse.
+///
+/// # Returns
+///
+/// `(Network, Vec<Diagnostic>)`: The Network struct and the warnings,
+/// in the order of the text.
+///
+/// # Example
+/// ```
+/// use ip_extractor::parse_network_lenient;
+///
+/// let (network, warnings) = parse_network_lenient("wlp2s0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500
+///         inet
+///         ether 3c:a9:f4:12:34:56  txqueuelen 1000  (Ethernet)
+/// ");
+///
+/// assert_eq!(network.mac.as_deref(), Some("3c:a9:f4:12:34:56"));
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(warnings[0].line, 2);
+/// assert_eq!(warnings[0].expected, "an IPv4 address");
+/// ```
+pub fn parse_network_lenient(text: &str) -> (Network, Vec<Diagnostic>) {
+    (parse_network(text), diagnose(text, Dialect::detect(text)))
+}
+
+/// # Parse Network Strict
+/// Parses the text of an interface like `parse_network`, but fails
+/// if anything in it is not what `ifconfig` prints. See
+/// `parse_network_lenient` for what is checked.
+///
+/// # Arguments
+///
+/// * `text`: The string of text from `ifconfig` to parse.
+///
+/// # Returns
+///
+/// `Result<Network, NetworkError>`: The Network struct, or a
+/// `NetworkError::Syntax` with every Diagnostic of the text.
+///
+/// # Example
+/// ```
+/// use ip_extractor::{parse_network_strict, NetworkError};
+///
+/// let error = parse_network_strict("wlp2s0 flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500\n");
+///
+/// match error {
+///     Err(NetworkError::Syntax(diagnostics)) => {
+///         assert_eq!(diagnostics[0].column, 1);
+///         assert_eq!(diagnostics[0].found, "wlp2s0");
+///     }
+///     _ => panic!("the name is not followed by a colon"),
+/// }
+/// ```
+pub fn parse_network_strict(text: &str) -> Result<Network, NetworkError> {
+    match parse_network_lenient(text) {
+        (network, diagnostics) if diagnostics.is_empty() => Ok(network),
+        (_, diagnostics) => Err(NetworkError::Syntax(diagnostics)),
+    }
+}
+
+/// Internal method to check the text of an interface against what its
+/// dialect looks like. Lines that are not known, like `status: active`,
+/// are not checked, as they are kept in `extra`.
+fn diagnose(text: &str, dialect: Dialect) -> Vec<Diagnostic> {
+    let mut diagnostics = Diagnostics::new();
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .skip_while(|(_, line)| line.trim().is_empty());
+
+    let (number, header) = match lines.next() {
+        Some(header) => header,
+        None => {
+            diagnostics.push(1, "", None, "the name of an interface");
+            return diagnostics.list;
+        }
+    };
+
+    let tokens = columns(header);
+    match dialect {
+        Dialect::Legacy => diagnose_legacy(&mut diagnostics, number, header),
+        Dialect::NetTools | Dialect::Bsd => {
+            diagnostics.check(
+                number,
+                header,
+                tokens.first().copied(),
+                "`:` after the name of the interface",
+                |x| x.len() > 1 && x.ends_with(':'),
+            );
+
+            match tokens.iter().position(|(_, x)| x.starts_with("flags=")) {
+                Some(i) => diagnostics.check(number, header, Some(tokens[i]), "the flags", |x| {
+                    let flags = x.trim_start_matches("flags=");
+                    flags
+                        .split('<')
+                        .next()
+                        .unwrap_or_default()
+                        .parse::<u32>()
+                        .is_ok()
+                }),
+                None => diagnostics.push(number, header, tokens.get(1).copied(), "`flags=`"),
+            }
+        }
+    }
+    diagnose_values(
+        &mut diagnostics,
+        number,
+        header,
+        &tokens,
+        &[("mtu", "a number", is_number)],
+    );
+
+    for (number, line) in lines {
+        let tokens = columns(line);
+        match dialect {
+            Dialect::Legacy => diagnose_legacy(&mut diagnostics, number, line),
+            Dialect::NetTools | Dialect::Bsd => match tokens.first().map(|x| x.1) {
+                Some("inet") => {
+                    diagnostics.check(number, line, tokens.get(1).copied(), IPV4, is_ipv4);
+                    diagnose_values(
+                        &mut diagnostics,
+                        number,
+                        line,
+                        &tokens[1..],
+                        &[
+                            ("netmask", "a netmask", is_netmask),
+                            ("broadcast", IPV4, is_ipv4),
+                            ("destination", IPV4, is_ipv4),
+                            ("-->", IPV4, is_ipv4),
+                        ],
+                    );
+                }
+                Some("inet6") => {
+                    diagnostics.check(number, line, tokens.get(1).copied(), IPV6, |x| {
+                        x.split('%')
+                            .next()
+                            .unwrap_or_default()
+                            .parse::<Ipv6Addr>()
+                            .is_ok()
+                    });
+                    diagnose_values(
+                        &mut diagnostics,
+                        number,
+                        line,
+                        &tokens[1..],
+                        &[("prefixlen", "a prefix length", is_prefix_len)],
+                    );
+                }
+                Some("ether") => {
+                    diagnostics.check(number, line, tokens.get(1).copied(), MAC, is_mac)
+                }
+                _ => {}
+            },
+        }
+    }
+
+    diagnostics.list
+}
+
+const IPV4: &str = "an IPv4 address";
+const IPV6: &str = "an IPv6 address";
+const MAC: &str = "a MAC address";
+
+fn is_number(text: &str) -> bool {
+    text.parse::<u32>().is_ok()
+}
+
+fn is_ipv4(text: &str) -> bool {
+    text.parse::<Ipv4Addr>().is_ok()
+}
+
+fn is_netmask(text: &str) -> bool {
+    text.parse::<Netmask>().is_ok()
+}
+
+fn is_prefix_len(text: &str) -> bool {
+    text.parse::<u8>().is_ok_and(|x| x <= 128)
+}
+
+fn is_mac(text: &str) -> bool {
+    text.parse::<MacAddr>().is_ok()
+}
+
+/// A key, what its value is expected to be and how it is checked,
+/// like `("mtu", "a number", is_number)`.
+type Expected = (&'static str, &'static str, fn(&str) -> bool);
+
+/// Internal method to check that the keys of a line, like `netmask`,
+/// are followed by a valid value.
+fn diagnose_values(
+    diagnostics: &mut Diagnostics,
+    number: usize,
+    line: &str,
+    tokens: &[(usize, &str)],
+    keys: &[Expected],
+) {
+    for (i, (_, token)) in tokens.iter().enumerate() {
+        if let Some((_, expected, valid)) = keys.iter().find(|(key, _, _)| key == token) {
+            diagnostics.check(number, line, tokens.get(i + 1).copied(), expected, valid);
+        }
+    }
+}
+
+/// Internal method to check a line of the older net-tools and BusyBox
+/// output, where the values follow their key after a colon, like
+/// `inet addr:10.0.0.5` or `MTU:1500`.
+fn diagnose_legacy(diagnostics: &mut Diagnostics, number: usize, line: &str) {
+    let tokens = columns(line);
+
+    for (i, &(column, token)) in tokens.iter().enumerate() {
+        let after = |prefix: &str| {
+            let value = token.strip_prefix(prefix)?;
+            match value.is_empty() {
+                true => Some(tokens.get(i + 1).copied()),
+                false => Some(Some((column + prefix.chars().count(), value))),
+            }
+        };
+
+        if token == "HWaddr" {
+            // Links that are not Ethernet, like tunnels, have longer addresses.
+            diagnostics.check(number, line, tokens.get(i + 1).copied(), MAC, |x| {
+                x.split(['-', ':'])
+                    .all(|x| x.len() == 2 && u8::from_str_radix(x, 16).is_ok())
+            });
+        } else if let Some(value) = after("MTU:") {
+            diagnostics.check(number, line, value, "a number", is_number);
+        } else if let Some(value) = after("Mask:") {
+            diagnostics.check(number, line, value, "a netmask", is_netmask);
+        } else if let Some(value) = after("Bcast:").or_else(|| after("P-t-P:")) {
+            diagnostics.check(number, line, value, IPV4, is_ipv4);
+        } else if i == 1 && tokens[0].1 == "inet" {
+            let value = after("addr:").unwrap_or(Some((column, token)));
+            diagnostics.check(number, line, value, IPV4, is_ipv4);
+        } else if i == 1 && tokens[0].1 == "inet6" {
+            let value = after("addr:").unwrap_or(Some((column, token)));
+            diagnostics.check(number, line, value, IPV6, |x| {
+                let (address, prefix_len) = x.split_once('/').unwrap_or((x, "128"));
+                address.parse::<Ipv6Addr>().is_ok() && is_prefix_len(prefix_len)
+            });
+        }
+    }
+
+    if tokens.len() == 1 && matches!(tokens[0].1, "inet" | "inet6") {
+        let expected = match tokens[0].1 {
+            "inet" => IPV4,
+            _ => IPV6,
+        };
+        diagnostics.push(number, line, None, expected);
+    }
+}
+
+/// Internal method to collect the fields of the text of an interface
+/// that the parser of its dialect does not read, like `txqueuelen 1000`,
+/// `device interrupt 16` or BSD's `status: active`.
+fn extra_fields(text: &str, dialect: Dialect) -> BTreeMap<String, String> {
+    let mut extra = BTreeMap::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+
+        if i == 0 {
+            // The first token is the name, and the legacy `Link encap:`
+            // can be a few words, like `Local Loopback`, up to `HWaddr`.
+            let (rest, known) = match dialect {
+                Dialect::Legacy => {
+                    let rest = line.find("HWaddr").map_or("", |x| &line[x..]);
+                    // A longer address, like the one of a tunnel, is not
+                    // read into `mac`, so it is kept here instead.
+                    match rest.split_whitespace().nth(1).is_some_and(is_mac) {
+                        true => (rest, &[("HWaddr", 1)][..]),
+                        false => (rest, &[][..]),
+                    }
+                }
+                Dialect::NetTools | Dialect::Bsd => (
+                    line.split_once(char::is_whitespace).map_or("", |x| x.1),
+                    &[("flags", 0), ("mtu", 1)][..],
+                ),
+            };
+            unknown_fields(rest, |key| lookup(known, key), &mut extra);
+        } else if line.starts_with("inet ") || line.starts_with("inet6 ") {
+            let known = [
+                ("inet", 1),
+                ("inet6", 1),
+                ("netmask", 1),
+                ("broadcast", 1),
+                ("destination", 1),
+                ("-->", 1),
+                ("prefixlen", 1),
+                ("scopeid", 1),
+                ("duplicated", 0),
+                ("addr", 1),
+                ("Bcast", 1),
+                ("Mask", 1),
+                ("P-t-P", 1),
+                ("Scope", 1),
+            ];
+            unknown_fields(
+                line,
+                |key| lookup(&known, key).or_else(|| AddressFlags::from_name(key).map(|_| 0)),
+                &mut extra,
+            );
+        } else if InterfaceStats::default().parse_ifconfig_line(line) {
+            let known = [
+                ("RX", 0),
+                ("TX", 0),
+                ("packets", 1),
+                ("bytes", 1),
+                ("errors", 1),
+                ("dropped", 1),
+                ("overruns", 1),
+                ("frame", 1),
+                ("carrier", 1),
+                ("collisions", 1),
+                ("compressed", 1),
+                ("missed", 1),
+            ];
+            unknown_fields(line, |key| lookup(&known, key), &mut extra);
+        } else if dialect == Dialect::Legacy && line.contains("MTU:") {
+            unknown_fields(
+                line,
+                |key| match key {
+                    "MTU" => Some(1),
+                    _ => InterfaceFlags::from_name(key).map(|_| 0),
+                },
+                &mut extra,
+            );
+        } else {
+            let known = [("ether", 1), ("loop", 0), ("nd6", REST)];
+            unknown_fields(line, |key| lookup(&known, key), &mut extra);
+        }
+    }
+
+    extra
+}
+
+/// Internal method to parse the output of net-tools 2.x, which
+/// is what current Linux distributions ship.
+fn parse_net_tools(line: &str) -> Network {
+    let mut network = Network {
+        name: header_name(line),
+        ..Default::default()
+    };
+
+    if let Some(header) = line.lines().next() {
+        parse_header(&mut network, header);
+    }
+
+    for inet in line.lines().filter_map(|x| x.trim().strip_prefix("inet ")) {
+        parse_inet(&mut network, inet);
+    }
+
+    for inet6 in line.lines().filter_map(|x| x.trim().strip_prefix("inet6 ")) {
+        parse_inet6(&mut network, inet6, Dialect::NetTools);
+    }
+
+    let mut stats = InterfaceStats::default();
+    let mut has_stats = false;
+    for x in line.lines() {
+        has_stats |= stats.parse_ifconfig_line(x);
+    }
+    if has_stats {
+        network.stats = Some(stats);
+    }
+
+    network.mac = line
+        .lines()
+        .filter_map(|x| x.trim().strip_prefix("ether "))
+        .filter_map(|x| x.split_whitespace().next())
+        .find(|x| is_mac(x))
+        .map(|x| x.to_string());
+
+    network
+}
+
+/// Internal method to get the name of an interface from its first
+/// word, which ends in a colon, like `eth0:` or `eth0:1:` for an alias.
+/// If the colon is missing, the word is still taken instead of the
+/// whole text.
+fn header_name(text: &str) -> String {
+    let word = text.split_whitespace().next().unwrap_or_default();
+    word.strip_suffix(':').unwrap_or(word).to_string()
+}
+
+/// Internal method to work out the parent of an alias, like `eth0:1`,
+/// or of a VLAN, like `eth0.100`, from the name. `ifconfig` does not
+/// print the parent of other stacked interfaces.
+fn parse_name(network: &mut Network) {
+    if let Some((parent, _)) = network.name.split_once(':') {
+        network.label = Some(network.name.clone());
+        network.parent = Some(parent.to_string());
+    } else if let Some((parent, id)) = network.name.rsplit_once('.') {
+        if !parent.is_empty() && !id.is_empty() && id.bytes().all(|x| x.is_ascii_digit()) {
+            network.parent = Some(parent.to_string());
+        }
+    }
+}
+
+/// Internal method to parse the first line of an interface, like
+/// `eth0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500`.
+/// The names of the flags are preferred over the number, as other
+/// systems use different numbers for the same flags.
+fn parse_header(network: &mut Network, header: &str) {
+    let mut tokens = header.split_whitespace();
+    while let Some(token) = tokens.next() {
+        if let Some(flags) = token.strip_prefix("flags=") {
+            let (number, names) = flags.split_once('<').unwrap_or((flags, ""));
+            network.raw_flags = number.parse().ok();
+            network.flags = if names.is_empty() {
+                InterfaceFlags::from_bits(network.raw_flags.unwrap_or_default())
+            } else {
+                InterfaceFlags::from_names(names.trim_end_matches('>').split(','))
+            };
+        } else if token == "mtu" {
+            network.mtu = tokens.next().and_then(|x| x.parse().ok());
+        }
+    }
+}
+
+/// Internal method to parse the rest of an `inet` line, like
+/// `10.0.0.5  netmask 255.255.255.0  broadcast 10.0.0.255`.
+fn parse_inet(network: &mut Network, text: &str) {
+    let mut tokens = text.split_whitespace();
+    let inet = match tokens.next() {
+        Some(inet) => inet,
+        None => return,
+    };
+
+    let mut netmask = None;
+    let mut broadcast = None;
+    let mut peer = None;
+    while let Some(token) = tokens.next() {
+        match token {
+            "netmask" => netmask = tokens.next(),
+            "broadcast" => broadcast = tokens.next(),
+            // Linux prints `destination`, BSD prints `-->`.
+            "destination" | "-->" => peer = tokens.next(),
+            _ => {}
+        }
+    }
+
+    add_inet(network, inet, netmask, broadcast, peer);
+}
+
+/// Internal method to add an IPv4 address as it was written.
+/// The first one also fills in the primary address fields, even
+/// if they are not valid addresses.
+fn add_inet(
+    network: &mut Network,
+    inet: &str,
+    netmask: Option<&str>,
+    broadcast: Option<&str>,
+    peer: Option<&str>,
+) {
+    // BSD prints hex netmasks, which are kept in the dotted form.
+    let netmask = netmask.map(|x| match x.starts_with("0x") {
+        true => x
+            .parse::<Netmask>()
+            .map(|x| x.to_string())
+            .unwrap_or(x.to_string()),
+        false => x.to_string(),
+    });
+    let netmask = netmask.as_deref();
+
+    if network.inet.is_none() {
+        network.inet = Some(inet.to_string());
+        network.netmask = netmask.map(|x| x.to_string());
+        network.broadcast = broadcast.map(|x| x.to_string());
+    }
+
+    if let Ok(address) = inet.parse::<Ipv4Addr>() {
+        let prefix_len = netmask
+            .and_then(|x| x.parse::<Netmask>().ok())
+            .map(|x| x.prefix_len())
+            .unwrap_or(32);
+
+        network.inet_addresses.push(InetAddress {
+            broadcast: broadcast.and_then(|x| x.parse().ok()),
+            peer: peer.and_then(|x| x.parse().ok()),
+            ..InetAddress::new(address, prefix_len)
+        });
+    }
+}
+
+/// Internal method to parse the rest of an `inet6` line, like
+/// `fe80::1  prefixlen 64  scopeid 0x20<link>`.
+/// If there is no `scopeid`, the scope is worked out from the
+/// address itself. So is it on BSD, where the `scopeid` is the
+/// index of the interface instead of the scope.
+fn parse_inet6(network: &mut Network, text: &str, dialect: Dialect) {
+    let mut tokens = text.split_whitespace();
+    let address = match tokens
+        .next()
+        .and_then(|x| x.split('%').next()?.parse().ok())
+    {
+        Some(address) => address,
+        None => return,
+    };
+
+    let mut prefix_len = 128;
+    let mut scope = None;
+    let mut flags = AddressFlags::default();
+    while let Some(token) = tokens.next() {
+        match token {
+            "prefixlen" => {
+                if let Some(value) = tokens.next().and_then(|x| x.parse().ok()) {
+                    prefix_len = value;
+                }
+            }
+            "scopeid" if dialect == Dialect::Bsd => {
+                tokens.next();
+            }
+            "scopeid" => scope = tokens.next().and_then(Scope::from_scopeid),
+            "duplicated" => flags.insert(AddressFlags::DADFAILED),
+            _ => {
+                if let Some(flag) = AddressFlags::from_name(token) {
+                    flags.insert(flag);
+                }
+            }
+        }
+    }
+
+    network.inet6_addresses.push(Inet6Address {
+        scope: scope.unwrap_or_else(|| Scope::of(&address)),
+        flags,
+        ..Inet6Address::new(address, prefix_len)
+    });
+}
+
+/// Internal method to parse the FreeBSD and macOS output:
+///
+/// ```text
+/// en0: flags=8863<UP,BROADCAST,SMART,RUNNING,SIMPLEX,MULTICAST> mtu 1500
+///     ether a4:83:e7:12:34:56
+///     inet 192.168.1.23 netmask 0xffffff00 broadcast 192.168.1.255
+///     inet6 fe80::1c7f:2a1b:3c4d:5e6f%en0 prefixlen 64 secured scopeid 0x6
+///     status: active
+/// ```
+fn parse_bsd(text: &str) -> Network {
+    let mut network = Network {
+        name: header_name(text),
+        ..Default::default()
+    };
+
+    if let Some(header) = text.lines().next() {
+        parse_header(&mut network, header);
+    }
+
+    for line in text.lines().skip(1).map(|x| x.trim()) {
+        if let Some(inet) = line.strip_prefix("inet ") {
+            parse_inet(&mut network, inet);
+        } else if let Some(inet6) = line.strip_prefix("inet6 ") {
+            parse_inet6(&mut network, inet6, Dialect::Bsd);
+        } else if let Some(ether) = line.strip_prefix("ether ") {
+            network.mac = ether.split_whitespace().next().map(|x| x.to_string());
+        } else if let Some(status) = line.strip_prefix("status:") {
+            network.operstate = Some(bsd_status(status.trim()));
+        }
+    }
+
+    network
+}
+
+/// Internal method to get the operational state from the `status:`
+/// line, like `active` or `no carrier`. Wireless interfaces print
+/// `associated` instead of `active` on FreeBSD.
+fn bsd_status(status: &str) -> OperState {
+    match status {
+        "active" | "associated" | "running" => OperState::Up,
+        "no carrier" | "inactive" | "no network" => OperState::Down,
+        _ => OperState::Unknown,
+    }
+}
+
+/// Internal method to parse the older net-tools and BusyBox output:
+///
+/// ```text
+/// eth0      Link encap:Ethernet  HWaddr 00:0C:29:3E:5B:7A
+///           inet addr:192.168.1.10  Bcast:192.168.1.255  Mask:255.255.255.0
+///           inet6 addr: fe80::20c:29ff:fe3e:5b7a/64 Scope:Link
+///           UP BROADCAST RUNNING MULTICAST  MTU:1500  Metric:1
+///           RX packets:123456 errors:0 dropped:0 overruns:0 frame:0
+/// ```
+fn parse_legacy(text: &str) -> Network {
+    let mut network = Network {
+        name: text
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        ..Default::default()
+    };
+
+    let mut stats = InterfaceStats::default();
+    let mut has_stats = false;
+
+    for line in text.lines().map(|x| x.trim()) {
+        let tokens = line.split_whitespace().collect::<Vec<&str>>();
+
+        if let Some(i) = tokens.iter().position(|x| *x == "HWaddr") {
+            network.mac = tokens
+                .get(i + 1)
+                .filter(|x| is_mac(x))
+                .map(|x| x.to_lowercase());
+        }
+
+        if let Some(inet) = line.strip_prefix("inet addr:") {
+            parse_legacy_inet(&mut network, inet);
+        } else if let Some(inet6) = line.strip_prefix("inet6 addr:") {
+            parse_legacy_inet6(&mut network, inet6);
+        } else if tokens.iter().any(|x| x.starts_with("MTU:")) {
+            network.flags =
+                InterfaceFlags::from_names(tokens.iter().copied().take_while(|x| !x.contains(':')));
+            network.mtu = tokens
+                .iter()
+                .find_map(|x| x.strip_prefix("MTU:")?.parse().ok());
+        } else {
+            has_stats |= stats.parse_ifconfig_line(line);
+        }
+    }
+
+    if has_stats {
+        network.stats = Some(stats);
+    }
+
+    network
+}
+
+/// Internal method to parse the rest of a legacy `inet addr:` line, like
+/// `10.0.0.5  Bcast:10.0.0.255  Mask:255.255.255.0`.
+fn parse_legacy_inet(network: &mut Network, text: &str) {
+    let mut tokens = text.split_whitespace();
+    let inet = match tokens.next() {
+        Some(inet) => inet,
+        None => return,
+    };
+
+    let mut netmask = None;
+    let mut broadcast = None;
+    let mut peer = None;
+    for token in tokens {
+        match token.split_once(':') {
+            Some(("Mask", value)) => netmask = Some(value),
+            Some(("Bcast", value)) => broadcast = Some(value),
+            Some(("P-t-P", value)) => peer = Some(value),
+            _ => {}
+        }
+    }
+
+    add_inet(network, inet, netmask, broadcast, peer);
+}
+
+/// Internal method to parse the rest of a legacy `inet6 addr:` line,
+/// like `fe80::20c:29ff:fe3e:5b7a/64 Scope:Link`.
+fn parse_legacy_inet6(network: &mut Network, text: &str) {
+    let mut tokens = text.split_whitespace();
+    let (address, prefix_len) = match tokens
+        .next()
+        .map(|x| x.split_once('/').unwrap_or((x, "128")))
+    {
+        Some((address, prefix_len)) => (address, prefix_len),
+        None => return,
+    };
+    let address = match address.parse() {
+        Ok(address) => address,
+        Err(_) => return,
+    };
+
+    let scope = tokens.find_map(|x| x.strip_prefix("Scope:")).and_then(|x| {
+        match x.to_lowercase().as_str() {
+            "global" => Some(Scope::Global),
+            "site" => Some(Scope::Site),
+            "link" => Some(Scope::Link),
+            "host" => Some(Scope::Host),
+            _ => None,
+        }
+    });
+
+    network.inet6_addresses.push(Inet6Address {
+        scope: scope.unwrap_or_else(|| Scope::of(&address)),
+        ..Inet6Address::new(address, prefix_len.parse().unwrap_or(128))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_fixture(text: &str) -> Vec<Network> {
+        text.split("\n\n")
+            .filter(|x| !x.trim().is_empty())
+            .map(parse_network)
+            .collect()
+    }
+
+    #[test]
+    fn does_detect_dialect_work() {
+        assert_eq!(
+            Dialect::detect("eth0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500"),
+            Dialect::NetTools
+        );
+        assert_eq!(
+            Dialect::detect("lo        Link encap:Local Loopback"),
+            Dialect::Legacy
+        );
+    }
+
+    #[test]
+    fn does_split_interfaces_work() {
+        let text = "lo0: flags=8049<UP,LOOPBACK,RUNNING,MULTICAST> mtu 16384
+\tinet 127.0.0.1 netmask 0xff000000
+en0: flags=8863<UP,BROADCAST,SMART,RUNNING,SIMPLEX,MULTICAST> mtu 1500
+\tether a4:83:e7:12:34:56
+
+eth0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500
+";
+        let blocks = split_interfaces(text);
+
+        assert_eq!(blocks.len(), 3);
+        assert!(blocks[0].starts_with("lo0:"));
+        assert!(blocks[1].starts_with("en0:"));
+        assert!(blocks[2].starts_with("eth0:"));
+        assert_eq!(blocks.concat(), text);
+    }
+
+    #[test]
+    fn does_parse_macos_work() {
+        let networks = split_interfaces(include_str!("../../tests/fixtures/ifconfig-macos.txt"))
+            .into_iter()
+            .map(parse_network)
+            .collect::<Vec<Network>>();
+        assert_eq!(networks.len(), 6);
+
+        let lo0 = &networks[0];
+        assert_eq!(lo0.name, "lo0");
+        assert_eq!(lo0.netmask.as_deref(), Some("255.0.0.0"));
+        assert!(lo0.is_loopback());
+        assert_eq!(lo0.raw_flags, Some(8049));
+        assert_eq!(lo0.mtu, Some(16384));
+        assert_eq!(lo0.inet6_addresses[1].scope, Scope::Link);
+
+        let en0 = &networks[3];
+        assert_eq!(en0.name, "en0");
+        assert_eq!(en0.mac.as_deref(), Some("a4:83:e7:12:34:56"));
+        assert_eq!(en0.inet.as_deref(), Some("192.168.1.23"));
+        assert_eq!(en0.netmask.as_deref(), Some("255.255.255.0"));
+        assert_eq!(en0.broadcast.as_deref(), Some("192.168.1.255"));
+        assert_eq!(en0.inet_addresses[0].prefix_len, 24);
+        assert_eq!(en0.inet6_addresses.len(), 3);
+        assert_eq!(en0.inet6_addresses[0].scope, Scope::Link);
+        assert_eq!(en0.inet6_addresses[1].scope, Scope::Global);
+        assert!(en0.inet6_addresses[2].is_temporary());
+        // The flags of an address that are not parsed are kept too.
+        assert_eq!(en0.extra["secured"], "");
+        assert_eq!(en0.extra["autoconf"], "");
+        assert!(!en0.extra.contains_key("temporary"));
+        assert!(en0.is_up() && en0.is_running());
+        assert!(!en0.flags.contains(InterfaceFlags::LOOPBACK));
+        assert_eq!(en0.operstate, Some(OperState::Up));
+        assert_eq!(networks[4].oper_state(), OperState::Down);
+
+        // The loopback interface has no status, so it comes from the flags.
+        assert_eq!(lo0.operstate, None);
+        assert_eq!(lo0.oper_state(), OperState::Up);
+
+        assert_eq!(networks[5].name, "utun0");
+        assert!(networks[5].is_point_to_point());
+    }
+
+    #[test]
+    fn does_parse_freebsd_work() {
+        let networks = split_interfaces(include_str!("../../tests/fixtures/ifconfig-freebsd.txt"))
+            .into_iter()
+            .map(parse_network)
+            .collect::<Vec<Network>>();
+        assert_eq!(networks.len(), 2);
+
+        let em0 = &networks[0];
+        assert_eq!(em0.name, "em0");
+        assert_eq!(em0.mac.as_deref(), Some("08:00:27:ab:cd:ef"));
+        assert_eq!(em0.inet.as_deref(), Some("10.0.2.15"));
+        assert_eq!(em0.netmask.as_deref(), Some("255.255.255.0"));
+        assert_eq!(em0.mtu, Some(1500));
+
+        let lo0 = &networks[1];
+        assert_eq!(lo0.inet.as_deref(), Some("127.0.0.1"));
+        assert_eq!(lo0.inet6_addresses[0].scope, Scope::Host);
+    }
+
+    #[test]
+    fn does_parse_net_tools_1_60_work() {
+        let networks = parse_fixture(include_str!(
+            "../../tests/fixtures/ifconfig-net-tools-1.60.txt"
+        ));
+        assert_eq!(networks.len(), 2);
+
+        let eth0 = &networks[0];
+        assert_eq!(eth0.name, "eth0");
+        assert_eq!(eth0.inet.as_deref(), Some("192.168.1.10"));
+        assert_eq!(eth0.broadcast.as_deref(), Some("192.168.1.255"));
+        assert_eq!(eth0.netmask.as_deref(), Some("255.255.255.0"));
+        assert_eq!(eth0.mac.as_deref(), Some("00:0c:29:3e:5b:7a"));
+        assert_eq!(eth0.inet_addresses[0].prefix_len, 24);
+        assert_eq!(eth0.inet6_addresses[0].prefix_len, 64);
+        assert_eq!(eth0.inet6_addresses[0].scope, Scope::Link);
+        assert!(eth0.is_up() && eth0.is_running());
+        assert_eq!(eth0.mtu, Some(1500));
+
+        let stats = eth0.stats.unwrap();
+        assert_eq!(stats.rx_packets, 123456);
+        assert_eq!(stats.rx_dropped, 12);
+        assert_eq!(stats.rx_bytes, 98765432);
+        assert_eq!(stats.tx_bytes, 12345678);
+        assert_eq!(eth0.extra["txqueuelen"], "1000");
+        assert_eq!(eth0.extra["Interrupt"], "19");
+        assert_eq!(eth0.extra["Base address"], "0x2000");
+
+        let lo = &networks[1];
+        assert_eq!(lo.name, "lo");
+        assert_eq!(lo.mac, None);
+        assert!(lo.is_loopback());
+        assert_eq!(lo.inet6_addresses[0].scope, Scope::Host);
+    }
+
+    #[test]
+    fn does_parse_busybox_work() {
+        let networks = parse_fixture(include_str!("../../tests/fixtures/ifconfig-busybox.txt"));
+        assert_eq!(networks.len(), 3);
+
+        assert_eq!(networks[0].name, "eth0");
+        assert_eq!(networks[0].inet.as_deref(), Some("172.17.0.2"));
+        assert_eq!(networks[0].netmask.as_deref(), Some("255.255.0.0"));
+        assert_eq!(networks[0].broadcast.as_deref(), Some("172.17.255.255"));
+        assert_eq!(networks[0].mac.as_deref(), Some("02:42:ac:11:00:02"));
+
+        let tun0 = &networks[2];
+        assert_eq!(tun0.inet.as_deref(), Some("10.8.0.6"));
+        assert_eq!(tun0.broadcast, None);
+        assert_eq!(
+            tun0.inet_addresses[0].peer,
+            Some(Ipv4Addr::new(10, 8, 0, 5))
+        );
+        assert!(tun0.is_point_to_point());
+        assert!(tun0.flags.contains(InterfaceFlags::NOARP));
+        // The 16 bytes of a tunnel address are not a MAC address.
+        assert_eq!(tun0.mac, None);
+        assert_eq!(
+            tun0.extra["HWaddr"],
+            "00-00-00-00-00-00-00-00-00-00-00-00-00-00-00-00"
+        );
+    }
+
+    #[test]
+    fn does_parse_aliases_work() {
+        let networks = parse_fixture(include_str!("../../tests/fixtures/ifconfig-aliases.txt"));
+        let names = networks
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["eth0", "eth0:1", "eth0.100"]);
+
+        assert_eq!(networks[0].label, None);
+        assert_eq!(networks[0].parent, None);
+
+        let alias = &networks[1];
+        assert_eq!(alias.label.as_deref(), Some("eth0:1"));
+        assert_eq!(alias.parent.as_deref(), Some("eth0"));
+        assert_eq!(alias.inet.as_deref(), Some("10.0.0.6"));
+        assert_eq!(alias.stats, None);
+
+        let vlan = &networks[2];
+        assert_eq!(vlan.label, None);
+        assert_eq!(vlan.parent.as_deref(), Some("eth0"));
+
+        let legacy = parse_network("eth0:1    Link encap:Ethernet  HWaddr 00:0C:29:3E:5B:7A\n");
+        assert_eq!(legacy.name, "eth0:1");
+        assert_eq!(legacy.parent.as_deref(), Some("eth0"));
+    }
+
+    #[test]
+    fn does_parse_network_lenient_accept_fixtures() {
+        let fixtures = [
+            include_str!("../../tests/fixtures/ifconfig-aliases.txt"),
+            include_str!("../../tests/fixtures/ifconfig-busybox.txt"),
+            include_str!("../../tests/fixtures/ifconfig-freebsd.txt"),
+            include_str!("../../tests/fixtures/ifconfig-macos.txt"),
+            include_str!("../../tests/fixtures/ifconfig-net-tools-1.60.txt"),
+            include_str!(
+                "../../tests/fixtures/sosreport-ifconfig/sos_commands/networking/ifconfig_-a"
+            ),
+        ];
+
+        for block in fixtures.into_iter().flat_map(split_interfaces) {
+            let (network, warnings) = parse_network_lenient(block);
+            assert_eq!(warnings, vec![], "{}", block);
+            assert_eq!(parse_network_strict(block).unwrap(), network);
+        }
+    }
+
+    #[test]
+    fn does_parse_network_lenient_find_problems() {
+        let (network, warnings) = parse_network_lenient(
+            "wlp2s0 flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500\n\
+             \x20       inet\n\
+             \x20       inet6 fe80::1  prefixlen 200\n\
+             \x20       ether 3c:a9:f4:12:34\n",
+        );
+        assert_eq!(network.name, "wlp2s0");
+        assert_eq!(network.mac, None);
+
+        let found = warnings
+            .iter()
+            .map(|x| (x.line, x.column, x.found.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                (1, 1, "wlp2s0"),
+                (2, 13, ""),
+                (3, 34, "200"),
+                (4, 15, "3c:a9:f4:12:34"),
+            ]
+        );
+        assert_eq!(warnings[1].expected, "an IPv4 address");
+
+        let (_, warnings) = parse_network_lenient(
+            "eth0      Link encap:Ethernet  HWaddr 00:0C:29:3E:5B:7A\n\
+             \x20         inet addr:10.0.0.x  Bcast:10.0.0.255  Mask:255.255.0.255\n\
+             \x20         UP BROADCAST RUNNING MULTICAST  MTU:  Metric:1\n",
+        );
+        let found = warnings
+            .iter()
+            .map(|x| (x.line, x.column, x.found.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                (2, 21, "10.0.0.x"),
+                (2, 54, "255.255.0.255"),
+                (3, 49, "Metric:1")
+            ]
+        );
+
+        let (_, warnings) = parse_network_lenient("");
+        assert_eq!(warnings[0].expected, "the name of an interface");
+
+        assert!(matches!(
+            parse_network_strict("eth0: mtu 1500\n"),
+            Err(NetworkError::Syntax(x)) if x[0].expected == "`flags=`"
+        ));
+    }
+}
@@ -0,0 +1,815 @@
+//! # Iproute Parser
+//! Turns the output of iproute2's `ip` command, both the JSON and
+//! the text, into Network structs.
+
+use serde_json::Value;
+
+use super::fields::{lookup, unknown_pairs};
+use super::split_interfaces;
+use crate::{
+    AddressFlags, Inet6Address, InetAddress, InterfaceFlags, InterfaceKind, InterfaceStats,
+    Lifetime, Network, NetworkError, OperState, Scope,
+};
+
+/// # Parse Ip Json
+/// Parses the output of `ip -json addr show` into Network structs.
+/// The output of `ip -json -details -stats addr show` is understood
+/// too, which adds the traffic counters of every interface.
+///
+/// Unlike the text of `ifconfig`, this output is meant for machines,
+/// so it does not need any guessing and is the most reliable way to
+/// read the output of a command.
+///
+/// # Arguments
+///
+/// * `text`: The JSON printed by `ip -json addr show`.
+///
+/// # Returns
+///
+/// `Result<Vec<Network>, NetworkError>`: A vector of Network structs,
+/// or a `NetworkError::Parse` if the text is not the expected JSON.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::parse_ip_json;
+///
+/// let networks = parse_ip_json(r#"[{"ifindex": 1, "ifname": "lo", "flags": ["LOOPBACK", "UP"],
+///     "mtu": 65536, "link_type": "loopback", "addr_info": [{"family": "inet",
+///     "local": "127.0.0.1", "prefixlen": 8, "scope": "host"}]}]"#).unwrap();
+///
+/// assert_eq!(networks[0].inet.as_deref(), Some("127.0.0.1"));
+/// ```
+pub fn parse_ip_json(text: &str) -> Result<Vec<Network>, NetworkError> {
+    let value: Value = serde_json::from_str(text)
+        .map_err(|error| NetworkError::Parse(format!("invalid ip JSON: {}", error)))?;
+
+    let links = value
+        .as_array()
+        .ok_or_else(|| NetworkError::Parse("ip JSON is not an array".to_string()))?;
+
+    links.iter().map(parse_ip_json_link).collect()
+}
+
+/// The keys of an interface in `ip -json` that are read into fields of
+/// Network, all other keys are kept in `extra`.
+const JSON_KNOWN: [&str; 15] = [
+    "ifindex",
+    "ifname",
+    "flags",
+    "mtu",
+    "link",
+    "link_index",
+    "link_type",
+    "address",
+    "broadcast",
+    "master",
+    "operstate",
+    "altnames",
+    "addr_info",
+    "stats64",
+    "stats",
+];
+
+/// Internal method to turn one interface of `ip -json` into a Network.
+fn parse_ip_json_link(link: &Value) -> Result<Network, NetworkError> {
+    let name = link["ifname"]
+        .as_str()
+        .ok_or_else(|| NetworkError::Parse("ip JSON interface without ifname".to_string()))?;
+
+    let mut network = Network {
+        name: name.to_string(),
+        index: link["ifindex"].as_u64().map(|x| x as u32),
+        mtu: link["mtu"].as_u64().map(|x| x as u32),
+        link_type: link["link_type"].as_str().map(|x| x.to_string()),
+        master: link["master"].as_str().map(|x| x.to_string()),
+        operstate: link["operstate"].as_str().and_then(OperState::from_name),
+        altnames: strings(&link["altnames"]),
+        flags: InterfaceFlags::from_names(strings(&link["flags"]).iter().map(|x| x.as_str())),
+        ..Default::default()
+    };
+
+    if network.link_type.as_deref() == Some("ether") {
+        network.mac = link["address"].as_str().map(|x| x.to_string());
+    }
+
+    // The peer is only known by its index if it is in another namespace.
+    let peer = match (link["link"].as_str(), link["link_index"].as_u64()) {
+        (Some(peer), _) => Some(peer.to_string()),
+        (None, Some(index)) => Some(format!("if{}", index)),
+        (None, None) => None,
+    };
+    if let Some(peer) = peer {
+        network.set_peer(&peer);
+    }
+    network.link_kind = match link["linkinfo"]["info_kind"].as_str() {
+        Some("tun") if link["linkinfo"]["info_data"]["type"].as_str() == Some("tap") => {
+            Some(InterfaceKind::Tap)
+        }
+        Some(kind) => InterfaceKind::from_link_kind(kind),
+        None => None,
+    };
+    if network.link_kind == Some(InterfaceKind::Veth) {
+        network.parent = None;
+    }
+
+    for (key, value) in link.as_object().into_iter().flatten() {
+        if !JSON_KNOWN.contains(&key.as_str()) {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            network.extra.insert(key.clone(), value);
+        }
+    }
+
+    for address in link["addr_info"].as_array().into_iter().flatten() {
+        match address["family"].as_str() {
+            Some("inet") => parse_ip_json_inet(&mut network, address),
+            Some("inet6") => parse_ip_json_inet6(&mut network, address),
+            _ => {}
+        }
+    }
+
+    if let Some(stats) = link["stats64"].as_object() {
+        let rx = &stats["rx"];
+        let tx = &stats["tx"];
+        let counter = |value: &Value, key: &str| value[key].as_u64().unwrap_or_default();
+
+        network.stats = Some(InterfaceStats {
+            rx_bytes: counter(rx, "bytes"),
+            rx_packets: counter(rx, "packets"),
+            rx_errors: counter(rx, "errors"),
+            rx_dropped: counter(rx, "dropped"),
+            rx_overruns: counter(rx, "fifo_errors"),
+            rx_frame: counter(rx, "frame_errors"),
+            rx_missed: counter(rx, "missed_errors"),
+            multicast: counter(rx, "multicast"),
+            tx_bytes: counter(tx, "bytes"),
+            tx_packets: counter(tx, "packets"),
+            tx_errors: counter(tx, "errors"),
+            tx_dropped: counter(tx, "dropped"),
+            tx_overruns: counter(tx, "fifo_errors"),
+            tx_carrier: counter(tx, "carrier_errors"),
+            tx_collisions: counter(tx, "collisions"),
+            ..Default::default()
+        });
+    }
+
+    Ok(network)
+}
+
+fn parse_ip_json_inet(network: &mut Network, address: &Value) {
+    let local = match address["local"].as_str().and_then(|x| x.parse().ok()) {
+        Some(local) => local,
+        None => return,
+    };
+    let prefix_len = address["prefixlen"].as_u64().unwrap_or(32) as u8;
+
+    let mut inet = InetAddress::new(local, prefix_len);
+    inet.broadcast = address["broadcast"].as_str().and_then(|x| x.parse().ok());
+    inet.peer = address["address"].as_str().and_then(|x| x.parse().ok());
+    if let Some(scope) = address["scope"].as_str().and_then(Scope::from_name) {
+        inet.scope = scope;
+    }
+    inet.secondary = address["secondary"].as_bool() == Some(true);
+    inet.flags = json_flags(address);
+    inet.label = address["label"].as_str().map(|x| x.to_string());
+    inet.valid_lft = json_lifetime(&address["valid_life_time"]);
+    inet.preferred_lft = json_lifetime(&address["preferred_life_time"]);
+
+    network.push_inet(inet);
+}
+
+fn parse_ip_json_inet6(network: &mut Network, address: &Value) {
+    let local = match address["local"].as_str().and_then(|x| x.parse().ok()) {
+        Some(local) => local,
+        None => return,
+    };
+    let prefix_len = address["prefixlen"].as_u64().unwrap_or(128) as u8;
+
+    let mut inet6 = Inet6Address::new(local, prefix_len);
+    if let Some(scope) = address["scope"].as_str().and_then(Scope::from_name) {
+        inet6.scope = scope;
+    }
+    inet6.flags = json_flags(address);
+    inet6.valid_lft = json_lifetime(&address["valid_life_time"]);
+    inet6.preferred_lft = json_lifetime(&address["preferred_life_time"]);
+
+    network.inet6_addresses.push(inet6);
+}
+
+/// Every flag of an address that is set is printed as a key with the value true.
+fn json_flags(address: &Value) -> AddressFlags {
+    let mut flags = AddressFlags::default();
+    for (key, value) in address.as_object().into_iter().flatten() {
+        if value.as_bool() == Some(true) {
+            if let Some(flag) = AddressFlags::from_name(key) {
+                flags.insert(flag);
+            }
+        }
+    }
+
+    flags
+}
+
+fn json_lifetime(value: &Value) -> Option<Lifetime> {
+    value
+        .as_u64()
+        .map(|x| Lifetime::from_secs(x.min(u32::MAX as u64) as u32))
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|x| x.as_str().map(|x| x.to_string()))
+        .collect()
+}
+
+/// # Parse Ip Text
+/// Parses the text printed by `ip addr show` into Network structs.
+/// The text of `ip link show` and `ip -s link show` is understood
+/// too, since it is the same without the addresses, and so are the
+/// statistics blocks that `-s` adds to either of them.
+///
+/// Besides what `ifconfig` shows, this also reads the scope, flags,
+/// label and lifetimes (`valid_lft` and `preferred_lft`) of every
+/// address, and the `master` and `altname` of the interface.
+///
+/// # Arguments
+///
+/// * `text`: The text printed by `ip addr show`, for one or more interfaces.
+///
+/// # Returns
+///
+/// `Vec<Network>`: A vector of Network structs, one for every interface.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::parse_ip_text;
+///
+/// let networks = parse_ip_text("\
+/// 2: eth0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc fq_codel state UP qlen 1000
+///     link/ether 52:54:00:12:34:56 brd ff:ff:ff:ff:ff:ff
+///     inet 10.0.0.5/24 brd 10.0.0.255 scope global dynamic eth0
+///        valid_lft 86133sec preferred_lft 86133sec
+/// ");
+///
+/// assert_eq!(networks[0].inet.as_deref(), Some("10.0.0.5"));
+/// assert!(networks[0].inet_addresses[0].is_dynamic());
+/// ```
+pub fn parse_ip_text(text: &str) -> Vec<Network> {
+    split_interfaces(text)
+        .into_iter()
+        .filter_map(parse_ip_text_link)
+        .collect()
+}
+
+/// The kind of the last address line, which the `valid_lft` line after it belongs to.
+enum LastAddress {
+    None,
+    Inet,
+    Inet6,
+}
+
+/// Internal method to turn one interface of `ip addr show` into a Network:
+///
+/// ```text
+/// 2: enp3s0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc fq_codel master br0 state UP qlen 1000
+///     link/ether 52:54:00:12:34:56 brd ff:ff:ff:ff:ff:ff
+///     altname enx525400123456
+///     inet6 fe80::5054:ff:fe12:3456/64 scope link
+///        valid_lft forever preferred_lft forever
+/// ```
+fn parse_ip_text_link(block: &str) -> Option<Network> {
+    let mut lines = block.lines();
+    let header = lines.next()?;
+
+    // The header starts with the index and then the name,
+    // which can end in `@peer`, like `eth0.100@eth0`.
+    let (index, rest) = header.split_once(": ")?;
+    let mut tokens = rest.split_whitespace();
+    let name = tokens.next()?.trim_end_matches(':');
+    let (name, peer) = match name.split_once('@') {
+        Some((name, peer)) => (name, Some(peer)),
+        None => (name, None),
+    };
+
+    let mut network = Network {
+        name: name.to_string(),
+        index: index.trim().parse().ok(),
+        raw: Some(block.to_string()),
+        ..Default::default()
+    };
+    if let Some(peer) = peer {
+        network.set_peer(peer);
+    }
+
+    let rest = tokens.clone().collect::<Vec<&str>>().join(" ");
+    unknown_pairs(
+        &rest,
+        |key| match key.starts_with('<') {
+            true => Some(0),
+            false => lookup(&[("mtu", 1), ("master", 1), ("state", 1)], key),
+        },
+        &mut network.extra,
+    );
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "mtu" => network.mtu = tokens.next().and_then(|x| x.parse().ok()),
+            "master" => network.master = tokens.next().map(|x| x.to_string()),
+            "state" => network.operstate = tokens.next().and_then(OperState::from_name),
+            _ => {
+                if let Some(names) = token.strip_prefix('<').and_then(|x| x.strip_suffix('>')) {
+                    network.flags = InterfaceFlags::from_names(names.split(','));
+                }
+            }
+        }
+    }
+
+    let mut last = LastAddress::None;
+    let mut stats = None;
+    // Only the first detail line of `ip -d` names the kind of link.
+    let mut first_detail = true;
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+
+        if let Some(rest) = line.strip_prefix("link/") {
+            let mut tokens = rest.split_whitespace();
+            network.link_type = tokens.next().map(|x| x.to_string());
+            if network.link_type.as_deref() == Some("ether") {
+                network.mac = tokens.next().map(|x| x.to_string());
+            }
+            unknown_pairs(
+                line,
+                |key| match key.starts_with("link/") {
+                    true => Some(1),
+                    false => lookup(&[("brd", 1)], key),
+                },
+                &mut network.extra,
+            );
+        } else if let Some(altname) = line.strip_prefix("altname ") {
+            network.altnames.push(altname.trim().to_string());
+        } else if let Some(rest) = line.strip_prefix("inet ") {
+            parse_ip_text_inet(&mut network, rest);
+            last = LastAddress::Inet;
+        } else if let Some(rest) = line.strip_prefix("inet6 ") {
+            parse_ip_text_inet6(&mut network, rest);
+            last = LastAddress::Inet6;
+        } else if line.starts_with("valid_lft ") {
+            let (valid, preferred) = parse_lifetimes(line);
+            match last {
+                LastAddress::Inet => {
+                    if let Some(inet) = network.inet_addresses.last_mut() {
+                        inet.valid_lft = valid;
+                        inet.preferred_lft = preferred;
+                    }
+                }
+                LastAddress::Inet6 => {
+                    if let Some(inet6) = network.inet6_addresses.last_mut() {
+                        inet6.valid_lft = valid;
+                        inet6.preferred_lft = preferred;
+                    }
+                }
+                LastAddress::None => {}
+            }
+        } else if line.starts_with("RX") || line.starts_with("TX") {
+            // The names of the counters are on this line, their values on the next one.
+            let rx = line.starts_with("RX");
+            let names = line.split_once(':').map(|x| x.1).unwrap_or_default();
+            let values = lines.next().unwrap_or_default();
+            let stats = stats.get_or_insert_with(InterfaceStats::default);
+
+            for (name, value) in names.split_whitespace().zip(values.split_whitespace()) {
+                if let (Some(counter), Ok(value)) = (text_counter(stats, rx, name), value.parse()) {
+                    *counter = value;
+                }
+            }
+        } else if let Some((key, value)) = line.split_once(' ') {
+            // The details of `ip -d`, like `vlan protocol 802.1Q id 100`,
+            // are kept whole under their first word, which is the kind of link.
+            if first_detail {
+                network.link_kind = text_link_kind(key, value);
+                first_detail = false;
+            }
+            network
+                .extra
+                .insert(key.to_string(), value.trim().to_string());
+        } else if !line.is_empty() {
+            if first_detail {
+                network.link_kind = text_link_kind(line, "");
+                first_detail = false;
+            }
+            network.extra.insert(line.to_string(), String::new());
+        }
+    }
+    network.stats = stats;
+
+    // The details of `ip -d` tell a veth, whose peer is not its parent.
+    if network.link_kind == Some(InterfaceKind::Veth) {
+        network.parent = None;
+    }
+
+    Some(network)
+}
+
+/// The kind of link of a detail line of `ip -d`, like `tun type tap pi off`.
+/// A line like `bridge_slave state forwarding` is about the master of the
+/// interface, not about its own kind.
+fn text_link_kind(kind: &str, rest: &str) -> Option<InterfaceKind> {
+    if kind.ends_with("_slave") {
+        return None;
+    }
+
+    let tap = rest
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .windows(2)
+        .any(|x| x == ["type", "tap"]);
+
+    match kind {
+        "tun" if tap => Some(InterfaceKind::Tap),
+        kind => InterfaceKind::from_link_kind(kind),
+    }
+}
+
+/// Internal method to parse the rest of an `inet` line, like
+/// `192.168.1.20/24 brd 192.168.1.255 scope global secondary dynamic br0:1`.
+/// On point-to-point links it is `10.8.0.1 peer 10.8.0.2/32` instead,
+/// where the prefix length comes with the peer.
+fn parse_ip_text_inet(network: &mut Network, text: &str) {
+    let mut tokens = text.split_whitespace().peekable();
+    let (address, mut prefix_len) = match tokens.next().map(split_prefix) {
+        Some((address, prefix_len)) => (address, prefix_len),
+        None => return,
+    };
+    let address = match address.parse() {
+        Ok(address) => address,
+        Err(_) => return,
+    };
+
+    let mut inet = InetAddress::new(address, 32);
+    while let Some(token) = tokens.next() {
+        match token {
+            "brd" => inet.broadcast = tokens.next().and_then(|x| x.parse().ok()),
+            "peer" => {
+                if let Some((peer, peer_len)) = tokens.next().map(split_prefix) {
+                    inet.peer = peer.parse().ok();
+                    prefix_len = prefix_len.or(peer_len);
+                }
+            }
+            "scope" => {
+                if let Some(scope) = tokens.next().and_then(Scope::from_name) {
+                    inet.scope = scope;
+                }
+            }
+            "metric" | "proto" => {
+                tokens.next();
+            }
+            "secondary" => inet.secondary = true,
+            // Dynamic only means the address has a lifetime, which is on the next line.
+            "dynamic" => {}
+            _ => match AddressFlags::from_name(token) {
+                Some(flag) => inet.flags.insert(flag),
+                // The label is the last thing on the line, the name of
+                // the interface or an alias of it, like `br0:1`.
+                None if tokens.peek().is_none() && is_label(&network.name, token) => {
+                    inet.label = Some(token.to_string())
+                }
+                None => {}
+            },
+        }
+    }
+    inet.prefix_len = prefix_len.unwrap_or(32);
+
+    network.push_inet(inet);
+}
+
+/// Whether a token is the name of the interface or an alias of it.
+fn is_label(name: &str, token: &str) -> bool {
+    token == name || token.strip_prefix(name).is_some_and(|x| x.starts_with(':'))
+}
+
+/// Internal method to parse the rest of an `inet6` line, like
+/// `2001:db8::5/64 scope global temporary dynamic`.
+fn parse_ip_text_inet6(network: &mut Network, text: &str) {
+    let mut tokens = text.split_whitespace();
+    let (address, prefix_len) = match tokens.next().map(split_prefix) {
+        Some((address, prefix_len)) => (address, prefix_len),
+        None => return,
+    };
+    let address = match address.parse() {
+        Ok(address) => address,
+        Err(_) => return,
+    };
+
+    let mut inet6 = Inet6Address::new(address, prefix_len.unwrap_or(128));
+    while let Some(token) = tokens.next() {
+        match token {
+            "scope" => {
+                if let Some(scope) = tokens.next().and_then(Scope::from_name) {
+                    inet6.scope = scope;
+                }
+            }
+            "peer" | "metric" | "proto" => {
+                tokens.next();
+            }
+            _ => {
+                if let Some(flag) = AddressFlags::from_name(token) {
+                    inet6.flags.insert(flag);
+                }
+            }
+        }
+    }
+
+    network.inet6_addresses.push(inet6);
+}
+
+/// Splits an address like `10.0.0.5/24` into the address and the prefix length.
+fn split_prefix(text: &str) -> (&str, Option<u8>) {
+    match text.split_once('/') {
+        Some((address, prefix_len)) => (address, prefix_len.parse().ok()),
+        None => (text, None),
+    }
+}
+
+/// Parses a `valid_lft 86133sec preferred_lft 86133sec` line.
+fn parse_lifetimes(line: &str) -> (Option<Lifetime>, Option<Lifetime>) {
+    let mut valid = None;
+    let mut preferred = None;
+
+    let mut tokens = line.split_whitespace();
+    while let Some(token) = tokens.next() {
+        match token {
+            "valid_lft" => valid = tokens.next().and_then(|x| x.parse().ok()),
+            "preferred_lft" => preferred = tokens.next().and_then(|x| x.parse().ok()),
+            _ => {}
+        }
+    }
+
+    (valid, preferred)
+}
+
+/// Gets the counter of a column in the statistics of `ip -s link`.
+/// The `RX errors:` and `TX errors:` lines of `ip -s -s link` use
+/// the same names, so they end up in the same place. The `overrun`
+/// column is `rx_over_errors`, not the FIFO errors `ifconfig` calls
+/// overruns, so it is left out.
+fn text_counter<'a>(stats: &'a mut InterfaceStats, rx: bool, name: &str) -> Option<&'a mut u64> {
+    let counter = match (rx, name) {
+        (true, "bytes") => &mut stats.rx_bytes,
+        (true, "packets") => &mut stats.rx_packets,
+        (true, "errors") => &mut stats.rx_errors,
+        (true, "dropped") => &mut stats.rx_dropped,
+        (true, "fifo") => &mut stats.rx_overruns,
+        (true, "frame") => &mut stats.rx_frame,
+        (true, "missed") => &mut stats.rx_missed,
+        (true, "compressed") => &mut stats.rx_compressed,
+        (true, "mcast") => &mut stats.multicast,
+        (false, "bytes") => &mut stats.tx_bytes,
+        (false, "packets") => &mut stats.tx_packets,
+        (false, "errors") => &mut stats.tx_errors,
+        (false, "dropped") => &mut stats.tx_dropped,
+        (false, "fifo") => &mut stats.tx_overruns,
+        (false, "carrier") => &mut stats.tx_carrier,
+        (false, "collsns") => &mut stats.tx_collisions,
+        (false, "compressed") => &mut stats.tx_compressed,
+        _ => return None,
+    };
+
+    Some(counter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_parse_ip_json_work() {
+        let networks = parse_ip_json(include_str!(
+            "../../tests/fixtures/ip-json-details-addr.json"
+        ))
+        .unwrap();
+        assert_eq!(networks.len(), 4);
+
+        let lo = &networks[0];
+        assert_eq!(lo.name, "lo");
+        assert_eq!(lo.index, Some(1));
+        assert!(lo.is_loopback());
+        assert_eq!(lo.mac, None);
+        assert_eq!(lo.link_type.as_deref(), Some("loopback"));
+        assert_eq!(lo.inet_addresses[0].prefix_len, 8);
+        assert_eq!(lo.inet6_addresses[0].scope, Scope::Host);
+
+        let enp3s0 = &networks[1];
+        assert_eq!(enp3s0.name, "enp3s0");
+        assert_eq!(enp3s0.mac.as_deref(), Some("52:54:00:12:34:56"));
+        assert_eq!(enp3s0.master.as_deref(), Some("br0"));
+        assert_eq!(enp3s0.altnames, vec!["enx525400123456".to_string()]);
+        assert_eq!(enp3s0.mtu, Some(1500));
+        assert!(enp3s0.flags.contains(InterfaceFlags::LOWER_UP));
+        assert_eq!(enp3s0.stats.unwrap().rx_packets, 5021);
+        assert_eq!(enp3s0.stats.unwrap().tx_carrier, 2);
+
+        let br0 = &networks[2];
+        assert_eq!(br0.inet.as_deref(), Some("192.168.1.20"));
+        assert_eq!(br0.netmask.as_deref(), Some("255.255.255.0"));
+        assert_eq!(br0.broadcast.as_deref(), Some("192.168.1.255"));
+        assert_eq!(br0.inet_addresses.len(), 2);
+        assert_eq!(br0.inet6_addresses.len(), 2);
+        assert!(br0.inet6_addresses[0].is_temporary());
+        assert!(br0.inet6_addresses[0]
+            .flags
+            .contains(AddressFlags::NOPREFIXROUTE));
+        assert_eq!(br0.inet6_addresses[1].scope, Scope::Link);
+        assert_eq!(br0.link_kind, Some(InterfaceKind::Bridge));
+        assert_eq!(enp3s0.link_kind, None);
+        assert_eq!(enp3s0.kind(), InterfaceKind::Ethernet);
+
+        assert_eq!(networks[3].name, "wg0");
+        assert_eq!(networks[3].link_kind, Some(InterfaceKind::WireGuard));
+        assert_eq!(networks[3].link_type.as_deref(), Some("none"));
+        assert_eq!(networks[3].mac, None);
+    }
+
+    #[test]
+    fn does_parse_ip_json_find_peers() {
+        let networks = parse_ip_json(
+            r#"[{"ifindex": 5, "link": "eth0", "ifname": "eth0.100", "flags": ["UP"]},
+                {"ifindex": 7, "link_index": 2, "ifname": "veth0", "flags": ["UP"], "link_netnsid": 0},
+                {"ifindex": 8, "link": "veth2", "ifname": "veth1", "flags": ["UP"],
+                 "linkinfo": {"info_kind": "veth"}}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(networks[0].peer.as_deref(), Some("eth0"));
+        assert_eq!(networks[0].parent.as_deref(), Some("eth0"));
+        assert_eq!(networks[1].peer.as_deref(), Some("if2"));
+        assert_eq!(networks[1].parent, None);
+        assert_eq!(networks[2].peer.as_deref(), Some("veth2"));
+        assert_eq!(networks[2].parent, None);
+        assert!(!networks[0].extra.contains_key("link"));
+    }
+
+    #[test]
+    fn does_parse_ip_json_find_fifo_errors() {
+        let networks = parse_ip_json(
+            r#"[{"ifindex": 2, "ifname": "eth0", "flags": ["UP"], "stats64": {
+                "rx": {"bytes": 10, "over_errors": 7, "fifo_errors": 3, "frame_errors": 2},
+                "tx": {"bytes": 20, "fifo_errors": 4}}}]"#,
+        )
+        .unwrap();
+
+        let stats = networks[0].stats.unwrap();
+        assert_eq!(stats.rx_overruns, 3);
+        assert_eq!(stats.rx_frame, 2);
+        assert_eq!(stats.tx_overruns, 4);
+    }
+
+    #[test]
+    fn does_parse_ip_json_reject_garbage() {
+        assert!(matches!(
+            parse_ip_json("not json"),
+            Err(NetworkError::Parse(_))
+        ));
+        assert!(matches!(parse_ip_json("{}"), Err(NetworkError::Parse(_))));
+    }
+
+    #[test]
+    fn does_parse_ip_text_work() {
+        let networks = parse_ip_text(include_str!("../../tests/fixtures/ip-addr.txt"));
+        assert_eq!(networks.len(), 6);
+
+        let lo = &networks[0];
+        assert_eq!(lo.name, "lo");
+        assert_eq!(lo.index, Some(1));
+        assert_eq!(lo.mtu, Some(65536));
+        assert!(lo.is_loopback());
+        assert_eq!(lo.mac, None);
+        assert_eq!(lo.link_type.as_deref(), Some("loopback"));
+        assert_eq!(lo.inet_addresses[0].scope, Scope::Host);
+        assert_eq!(lo.inet_addresses[0].label.as_deref(), Some("lo"));
+        assert_eq!(lo.inet6_addresses[0].valid_lft, Some(Lifetime::Forever));
+        assert_eq!(lo.stats, None);
+
+        let enp3s0 = &networks[1];
+        assert_eq!(enp3s0.mac.as_deref(), Some("52:54:00:12:34:56"));
+        assert_eq!(enp3s0.master.as_deref(), Some("br0"));
+        assert_eq!(enp3s0.altnames, vec!["enx525400123456".to_string()]);
+        assert!(enp3s0.inet.is_none());
+
+        let br0 = &networks[2];
+        assert_eq!(br0.inet.as_deref(), Some("192.168.1.20"));
+        assert_eq!(br0.netmask.as_deref(), Some("255.255.255.0"));
+        assert_eq!(br0.broadcast.as_deref(), Some("192.168.1.255"));
+        assert!(br0.inet_addresses[0].is_dynamic());
+        assert!(!br0.inet_addresses[0].secondary);
+        assert!(br0.inet_addresses[0]
+            .flags
+            .contains(AddressFlags::NOPREFIXROUTE));
+        assert_eq!(
+            br0.inet_addresses[0].preferred_lft,
+            Some(Lifetime::Seconds(86133))
+        );
+        assert!(br0.inet_addresses[1].secondary);
+        assert!(!br0.inet_addresses[1].is_dynamic());
+        assert_eq!(br0.inet_addresses[1].label.as_deref(), Some("br0:1"));
+        assert!(br0.inet6_addresses[0].is_temporary());
+        assert_eq!(
+            br0.inet6_addresses[0].valid_lft,
+            Some(Lifetime::Seconds(604614))
+        );
+        assert_eq!(br0.inet6_addresses[1].scope, Scope::Link);
+
+        assert!(!networks[3].flags.contains(InterfaceFlags::RUNNING));
+        assert!(networks[3].flags.contains(InterfaceFlags::UP));
+        assert_eq!(networks[4].name, "br0.100");
+        assert_eq!(networks[4].peer.as_deref(), Some("br0"));
+        assert_eq!(networks[4].parent.as_deref(), Some("br0"));
+        assert_eq!(br0.parent, None);
+
+        let tun0 = &networks[5];
+        assert!(tun0.is_point_to_point());
+        assert_eq!(tun0.link_type.as_deref(), Some("none"));
+        assert_eq!(tun0.mac, None);
+        assert_eq!(tun0.inet.as_deref(), Some("10.8.0.1"));
+        assert_eq!(tun0.inet_addresses[0].prefix_len, 32);
+        assert_eq!(
+            tun0.inet_addresses[0].peer,
+            Some("10.8.0.2".parse().unwrap())
+        );
+        assert!(tun0.inet6_addresses[0]
+            .flags
+            .contains(AddressFlags::STABLE_PRIVACY));
+        assert_eq!(tun0.extra["qdisc"], "fq_codel");
+        assert_eq!(tun0.extra["qlen"], "500");
+        assert_eq!(tun0.operstate, Some(OperState::Unknown));
+        assert!(!tun0.extra.contains_key("state"));
+        assert!(tun0.raw.as_deref().unwrap().starts_with("6: tun0:"));
+    }
+
+    #[test]
+    fn does_parse_ip_text_find_kinds() {
+        let networks = parse_ip_text(
+            "\
+5: tap0: <BROADCAST,MULTICAST> mtu 1500 qdisc noop state DOWN mode DEFAULT qlen 1000
+    link/ether 9a:1c:3e:22:10:07 brd ff:ff:ff:ff:ff:ff promiscuity 0 minmtu 68 maxmtu 65521
+    tun type tap pi off vnet_hdr on persist on addrgenmode eui64
+6: veth1@veth2: <BROADCAST,MULTICAST,M-DOWN> mtu 1500 qdisc noop state DOWN mode DEFAULT qlen 1000
+    link/ether 2e:3f:10:aa:bb:cc brd ff:ff:ff:ff:ff:ff promiscuity 0 minmtu 68 maxmtu 65535
+    veth addrgenmode eui64 numtxqueues 1 numrxqueues 1
+7: enp3s0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc fq_codel master br0 state UP
+    link/ether 52:54:00:12:34:56 brd ff:ff:ff:ff:ff:ff promiscuity 1 minmtu 68 maxmtu 9000
+    bridge_slave state forwarding priority 32 cost 4 hairpin off
+    vlan protocol 802.1Q id 100 <REORDER_HDR>
+    inet 10.0.0.5/24 brd 10.0.0.255 scope global noprefixroute mystery enp3s0
+    inet 10.0.0.6/24 brd 10.0.0.255 scope global secondary enp3s0:1
+    inet 10.0.0.7/24 brd 10.0.0.255 scope global secondary enp3s00
+",
+        );
+
+        assert_eq!(networks[0].link_kind, Some(InterfaceKind::Tap));
+        assert_eq!(networks[1].link_kind, Some(InterfaceKind::Veth));
+        assert_eq!(networks[1].peer.as_deref(), Some("veth2"));
+        assert_eq!(networks[1].parent, None);
+
+        // A bridge port is not a bridge, and later lines are not the kind.
+        let enp3s0 = &networks[2];
+        assert_eq!(enp3s0.link_kind, None);
+        let labels = enp3s0
+            .inet_addresses
+            .iter()
+            .map(|x| x.label.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, [Some("enp3s0"), Some("enp3s0:1"), None]);
+    }
+
+    #[test]
+    fn does_parse_ip_text_find_stats() {
+        let networks = parse_ip_text(include_str!("../../tests/fixtures/ip-s-link.txt"));
+        assert_eq!(networks.len(), 3);
+
+        let stats = networks[1].stats.unwrap();
+        assert_eq!(stats.rx_bytes, 734196123);
+        assert_eq!(stats.rx_packets, 581422);
+        assert_eq!(stats.rx_errors, 3);
+        assert_eq!(stats.rx_dropped, 17);
+        assert_eq!(stats.rx_missed, 1);
+        assert_eq!(stats.multicast, 2048);
+        assert_eq!(stats.tx_bytes, 52993001);
+        assert_eq!(stats.tx_carrier, 2);
+        assert_eq!(networks[1].altnames.len(), 1);
+
+        let stats = networks[2].stats.unwrap();
+        assert_eq!(stats.rx_bytes, 1200);
+        assert_eq!(stats.rx_frame, 4);
+        assert_eq!(stats.rx_overruns, 5);
+        assert_eq!(stats.tx_packets, 5);
+        assert_eq!(stats.tx_overruns, 6);
+        assert!(!networks[2].is_up());
+    }
+}
@@ -0,0 +1,242 @@
+//! # Parser
+//! The parsers that turn the text printed by network tools
+//! into Network structs.
+
+use std::io::Read;
+use std::path::Path;
+
+use crate::{Network, NetworkError};
+
+mod diagnostic;
+mod fields;
+mod ifconfig;
+mod iproute;
+
+pub use diagnostic::Diagnostic;
+pub(crate) use ifconfig::split_interfaces;
+pub use ifconfig::{parse_network, parse_network_lenient, parse_network_strict};
+pub use iproute::{parse_ip_json, parse_ip_text};
+
+/// The formats `parse_networks` understands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Ifconfig,
+    IpText,
+    IpJson,
+}
+
+impl Format {
+    /// Works out the format from the first line that is not blank.
+    /// `ip` starts every interface with its index, like `2: eth0: <...>`,
+    /// while `ifconfig` starts with the name.
+    fn detect(text: &str) -> Format {
+        let first = text.lines().map(|x| x.trim()).find(|x| !x.is_empty());
+
+        match first {
+            Some(line) if line.starts_with('[') => Format::IpJson,
+            Some(line) => match line.split_once(": ") {
+                Some((index, _)) if index.parse::<u32>().is_ok() => Format::IpText,
+                _ => Format::Ifconfig,
+            },
+            None => Format::Ifconfig,
+        }
+    }
+}
+
+/// # Parse Networks
+/// Parses the full output of a network tool into Network structs,
+/// one for every interface in it. The output can be from `ifconfig`
+/// or `ifconfig -a` in any of the dialects `parse_network` knows,
+/// from `ip addr show` or `ip -s link show`, or from `ip -json addr show`.
+/// Which one it is, is worked out from the text.
+///
+/// Interfaces that are down are kept, so nothing in the text is
+/// lost. Text that can not be parsed at all, like JSON that is not
+/// from `ip`, gives an empty vector.
+///
+/// # Arguments
+///
+/// * `text`: The text printed by the tool.
+///
+/// # Returns
+///
+/// `Vec<Network>`: A vector of Network structs.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::parse_networks;
+///
+/// let networks = parse_networks("\
+/// eth0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500
+///         inet 10.0.0.5  netmask 255.255.255.0  broadcast 10.0.0.255
+///
+/// lo: flags=73<UP,LOOPBACK,RUNNING>  mtu 65536
+///         inet 127.0.0.1  netmask 255.0.0.0
+/// ");
+///
+/// assert_eq!(networks.len(), 2);
+/// assert_eq!(networks[1].name, "lo");
+/// ```
+pub fn parse_networks(text: &str) -> Vec<Network> {
+    match Format::detect(text) {
+        Format::Ifconfig => split_interfaces(text)
+            .into_iter()
+            .map(parse_network)
+            .filter(|network| !network.name.is_empty())
+            .collect(),
+        Format::IpText => parse_ip_text(text),
+        Format::IpJson => parse_ip_json(text).unwrap_or_default(),
+    }
+}
+
+/// # From Reader
+/// Reads all of the text from a reader, like a log or a socket,
+/// and parses it with `parse_networks`.
+///
+/// # Arguments
+///
+/// * `reader`: The reader to read the text from.
+///
+/// # Returns
+///
+/// `Result<Vec<Network>, NetworkError>`: A vector of Network structs,
+/// or the error that stopped the text from being read.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::from_reader;
+///
+/// let text = "lo: flags=73<UP,LOOPBACK,RUNNING>  mtu 65536\n        inet 127.0.0.1  netmask 255.0.0.0\n";
+/// let networks = from_reader(text.as_bytes()).unwrap();
+///
+/// assert_eq!(networks[0].inet.as_deref(), Some("127.0.0.1"));
+/// ```
+pub fn from_reader(mut reader: impl Read) -> Result<Vec<Network>, NetworkError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    let text = String::from_utf8(bytes)
+        .map_err(|_| NetworkError::Parse("the text is not valid UTF-8".to_string()))?;
+
+    Ok(parse_networks(&text))
+}
+
+/// # From File
+/// Reads a saved capture, like the output of `ifconfig -a > capture.txt`,
+/// and parses it with `parse_networks`.
+///
+/// # Arguments
+///
+/// * `path`: The path of the file to read.
+///
+/// # Returns
+///
+/// `Result<Vec<Network>, NetworkError>`: A vector of Network structs,
+/// or the error that stopped the file from being read.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::from_file;
+///
+/// let networks = from_file("tests/fixtures/ifconfig-freebsd.txt").unwrap();
+///
+/// assert_eq!(networks[0].name, "em0");
+/// ```
+pub fn from_file(path: impl AsRef<Path>) -> Result<Vec<Network>, NetworkError> {
+    from_reader(std::fs::File::open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_detect_format_work() {
+        assert_eq!(
+            Format::detect("\n1: lo: <LOOPBACK,UP,LOWER_UP> mtu 65536"),
+            Format::IpText
+        );
+        assert_eq!(Format::detect("[{\"ifname\": \"lo\"}]"), Format::IpJson);
+        assert_eq!(
+            Format::detect("lo: flags=73<UP,LOOPBACK,RUNNING>  mtu 65536"),
+            Format::Ifconfig
+        );
+        assert_eq!(
+            Format::detect("lo        Link encap:Local Loopback"),
+            Format::Ifconfig
+        );
+        assert_eq!(Format::detect(""), Format::Ifconfig);
+    }
+
+    #[test]
+    fn does_parse_networks_work() {
+        let ifconfig = parse_networks(include_str!(
+            "../../tests/fixtures/ifconfig-net-tools-1.60.txt"
+        ));
+        assert_eq!(ifconfig.len(), 2);
+
+        let ip = parse_networks(include_str!("../../tests/fixtures/ip-addr.txt"));
+        assert_eq!(ip.len(), 6);
+        assert_eq!(ip[2].name, "br0");
+
+        let json = parse_networks(include_str!(
+            "../../tests/fixtures/ip-json-details-addr.json"
+        ));
+        assert_eq!(json.len(), 4);
+
+        // ifconfig -a keeps the interfaces that are down.
+        let all = parse_networks(include_str!(
+            "../../tests/fixtures/sosreport-ifconfig/sos_commands/networking/ifconfig_-a"
+        ));
+        assert_eq!(all.len(), 3);
+        assert!(!all[1].is_up());
+
+        assert!(parse_networks("").is_empty());
+        assert!(parse_networks("[1, 2]").is_empty());
+    }
+
+    #[test]
+    fn does_parse_networks_keep_raw_text() {
+        let fixtures = [
+            include_str!("../../tests/fixtures/ifconfig-busybox.txt"),
+            include_str!("../../tests/fixtures/ifconfig-freebsd.txt"),
+            include_str!("../../tests/fixtures/ifconfig-macos.txt"),
+            include_str!("../../tests/fixtures/ifconfig-net-tools-1.60.txt"),
+            include_str!("../../tests/fixtures/ip-addr.txt"),
+            include_str!("../../tests/fixtures/ip-s-link.txt"),
+        ];
+
+        for text in fixtures {
+            let raw = parse_networks(text)
+                .into_iter()
+                .map(|network| network.raw.unwrap())
+                .collect::<String>();
+            assert_eq!(raw, text);
+        }
+
+        let json = parse_networks(include_str!(
+            "../../tests/fixtures/ip-json-details-addr.json"
+        ));
+        assert_eq!(json[1].raw, None);
+        assert_eq!(json[1].extra["qdisc"], "fq_codel");
+        assert_eq!(json[1].extra["parentbus"], "pci");
+    }
+
+    #[test]
+    fn does_from_file_work() {
+        let networks = from_file("tests/fixtures/ifconfig-macos.txt").unwrap();
+        assert_eq!(networks.len(), 6);
+
+        assert!(matches!(
+            from_file("tests/fixtures/missing.txt"),
+            Err(NetworkError::Io(_))
+        ));
+        assert!(matches!(
+            from_reader(&b"\xff\xfe"[..]),
+            Err(NetworkError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,525 @@
+//! # Query
+//! Picks the network interfaces that match a set of conditions,
+//! like every wireless card that is up and has an IPv4 address.
+
+use std::net::IpAddr;
+use std::ops::{Bound, RangeBounds};
+
+use regex::Regex;
+
+use crate::backend::{self, Backend};
+use crate::{Cidr, InterfaceKind, Network, NetworkError};
+
+/// A condition an interface has to meet.
+#[derive(Clone, Debug)]
+enum Predicate {
+    Name(String),
+    NamePrefix(String),
+    NameGlob(String),
+    NameRegex(Regex),
+    Kind(InterfaceKind),
+    Up(bool),
+    Running(bool),
+    HasIpv4(bool),
+    HasIpv6(bool),
+    InCidr(Cidr),
+    MacVendor([u8; 3]),
+    Mtu(Bound<u32>, Bound<u32>),
+    Virtual(bool),
+    DefaultRoute(bool),
+}
+
+impl Predicate {
+    fn matches(&self, network: &Network, default_routes: &[String]) -> bool {
+        match self {
+            Predicate::Name(name) => network.name == *name,
+            Predicate::NamePrefix(prefix) => network.name.starts_with(prefix.as_str()),
+            Predicate::NameGlob(pattern) => glob_match(pattern, &network.name),
+            Predicate::NameRegex(regex) => regex.is_match(&network.name),
+            Predicate::Kind(kind) => network.kind() == *kind,
+            Predicate::Up(up) => network.is_up() == *up,
+            Predicate::Running(running) => network.is_running() == *running,
+            Predicate::HasIpv4(has) => {
+                (network.inet.is_some() || !network.inet_addresses.is_empty()) == *has
+            }
+            Predicate::HasIpv6(has) => network.inet6_addresses.is_empty() != *has,
+            Predicate::InCidr(cidr) => addresses(network).iter().any(|x| cidr.contains(x)),
+            Predicate::MacVendor(oui) => network.mac_addr().map(|x| x.oui()) == Some(*oui),
+            Predicate::Mtu(start, end) => network.mtu.is_some_and(|x| (*start, *end).contains(&x)),
+            Predicate::Virtual(virtual_) => network.kind().is_virtual() == *virtual_,
+            Predicate::DefaultRoute(has) => default_routes.contains(&network.name) == *has,
+        }
+    }
+}
+
+/// # Network Query
+/// Picks the network interfaces that match all of the conditions
+/// it is given, which are added by chaining its methods.
+///
+/// The interfaces are read once when the query is run, from
+/// `get_all_networks`, so the ones that are down are found too
+/// unless `up(true)` is added. They are returned sorted by their
+/// index and then their name, and every interface only once.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::{InterfaceKind, NetworkQuery};
+///
+/// let networks = NetworkQuery::new()
+///     .kind(InterfaceKind::Wireless)
+///     .up(true)
+///     .has_ipv4(true)
+///     .run();
+///
+/// for network in networks {
+///     println!("{}", network);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NetworkQuery {
+    predicates: Vec<Predicate>,
+}
+
+impl NetworkQuery {
+    /// Creates a query that matches every interface.
+    pub fn new() -> NetworkQuery {
+        NetworkQuery::default()
+    }
+
+    fn with(mut self, predicate: Predicate) -> NetworkQuery {
+        self.predicates.push(predicate);
+        self
+    }
+
+    /// Only matches the interface with exactly this name.
+    pub fn name(self, name: &str) -> NetworkQuery {
+        self.with(Predicate::Name(name.to_string()))
+    }
+
+    /// Only matches the interfaces whose name starts with the prefix, like `en`.
+    pub fn name_prefix(self, prefix: &str) -> NetworkQuery {
+        self.with(Predicate::NamePrefix(prefix.to_string()))
+    }
+
+    /// Only matches the interfaces whose name matches a shell glob,
+    /// like `wl*` or `eth[0-3]`. A `*` matches any text, a `?` any
+    /// one character and `[...]` one of the characters or ranges in
+    /// it, or none of them if it starts with `!`.
+    pub fn name_glob(self, pattern: &str) -> NetworkQuery {
+        self.with(Predicate::NameGlob(pattern.to_string()))
+    }
+
+    /// Only matches the interfaces whose name matches the regex,
+    /// anywhere in the name unless it is anchored with `^` and `$`.
+    pub fn name_regex(self, regex: Regex) -> NetworkQuery {
+        self.with(Predicate::NameRegex(regex))
+    }
+
+    /// Only matches the interfaces of a kind, see `Network::kind`.
+    pub fn kind(self, kind: InterfaceKind) -> NetworkQuery {
+        self.with(Predicate::Kind(kind))
+    }
+
+    /// Only matches the interfaces that are up, or the ones that are not.
+    pub fn up(self, up: bool) -> NetworkQuery {
+        self.with(Predicate::Up(up))
+    }
+
+    /// Only matches the interfaces that are running, or the ones that are not.
+    pub fn running(self, running: bool) -> NetworkQuery {
+        self.with(Predicate::Running(running))
+    }
+
+    /// Only matches the interfaces that have an IPv4 address, or the
+    /// ones that do not.
+    pub fn has_ipv4(self, has: bool) -> NetworkQuery {
+        self.with(Predicate::HasIpv4(has))
+    }
+
+    /// Only matches the interfaces that have an IPv6 address, or the
+    /// ones that do not.
+    pub fn has_ipv6(self, has: bool) -> NetworkQuery {
+        self.with(Predicate::HasIpv6(has))
+    }
+
+    /// Only matches the interfaces with an IPv4 or IPv6 address in
+    /// the block, like `192.168.0.0/16`.
+    pub fn in_cidr(self, cidr: Cidr) -> NetworkQuery {
+        self.with(Predicate::InCidr(cidr))
+    }
+
+    /// Only matches the interfaces whose MAC address starts with the
+    /// OUI of a vendor, like `[0x52, 0x54, 0x00]` for QEMU.
+    pub fn mac_vendor(self, oui: [u8; 3]) -> NetworkQuery {
+        self.with(Predicate::MacVendor(oui))
+    }
+
+    /// Only matches the interfaces whose MTU is in the range, like
+    /// `9000..` for jumbo frames. Interfaces without a known MTU
+    /// never match.
+    pub fn mtu(self, range: impl RangeBounds<u32>) -> NetworkQuery {
+        self.with(Predicate::Mtu(
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        ))
+    }
+
+    /// Only matches the virtual interfaces, like bridges and tunnels,
+    /// or the ones that are not, see `InterfaceKind::is_virtual`.
+    pub fn is_virtual(self, virtual_: bool) -> NetworkQuery {
+        self.with(Predicate::Virtual(virtual_))
+    }
+
+    /// Only matches the interfaces with a default route, or the ones
+    /// without, see `Backend::default_routes`.
+    pub fn has_default_route(self, has: bool) -> NetworkQuery {
+        self.with(Predicate::DefaultRoute(has))
+    }
+
+    /// Runs the query against the interfaces of this system.
+    /// If they can not be read, nothing matches.
+    pub fn run(&self) -> Vec<Network> {
+        self.try_run().unwrap_or_default()
+    }
+
+    /// The fallible version of `run`. The interfaces and the default
+    /// routes are both read from the first backend that succeeds.
+    pub fn try_run(&self) -> Result<Vec<Network>, NetworkError> {
+        backend::auto(|backend| self.try_run_with(backend))
+    }
+
+    /// Runs the query against the interfaces of the given backend.
+    /// If they can not be read, nothing matches.
+    pub fn run_with(&self, backend: &dyn Backend) -> Vec<Network> {
+        self.try_run_with(backend).unwrap_or_default()
+    }
+
+    /// The fallible version of `run_with`.
+    pub fn try_run_with(&self, backend: &dyn Backend) -> Result<Vec<Network>, NetworkError> {
+        let default_routes = match self.needs_default_routes() {
+            true => backend.default_routes()?,
+            false => Vec::new(),
+        };
+
+        Ok(self.select(backend.all_networks()?, &default_routes))
+    }
+
+    fn needs_default_routes(&self) -> bool {
+        self.predicates
+            .iter()
+            .any(|x| matches!(x, Predicate::DefaultRoute(_)))
+    }
+
+    fn select(&self, networks: Vec<Network>, default_routes: &[String]) -> Vec<Network> {
+        let mut networks = networks
+            .into_iter()
+            .filter(|network| {
+                self.predicates
+                    .iter()
+                    .all(|x| x.matches(network, default_routes))
+            })
+            .collect::<Vec<Network>>();
+
+        // Interfaces without an index, like from `ifconfig`, go last.
+        networks.sort_by(|a, b| {
+            (a.index.is_none(), a.index, &a.name).cmp(&(b.index.is_none(), b.index, &b.name))
+        });
+        networks.dedup_by(|a, b| a.name == b.name);
+
+        networks
+    }
+}
+
+/// # Match Mode
+/// How `find_networks` and `find_network_by` match the name of an
+/// interface against the text they are given:
+/// * Exact: The name is the text.
+/// * Prefix: The name starts with the text, like `en` for `enp3s0`.
+/// * Suffix: The name ends with the text, like `s0` for `wlp2s0`.
+/// * Glob: The name matches the text as a shell glob, like `en*`,
+///   see `NetworkQuery::name_glob`.
+/// * Contains: The text is anywhere in the name, which is what
+///   `find_network` does.
+///
+/// Whether the case of the letters is ignored is up to the caller,
+/// with the `ignore_case` argument.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::MatchMode;
+///
+/// assert!(MatchMode::Prefix.matches("en", "enp3s0", false));
+/// assert!(!MatchMode::Prefix.matches("en", "veth0", false));
+/// assert!(MatchMode::Exact.matches("ETH0", "eth0", true));
+/// assert!(MatchMode::Glob.matches("wl*", "wlp2s0", false));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MatchMode {
+    Exact,
+    Prefix,
+    Suffix,
+    Glob,
+    Contains,
+}
+
+impl MatchMode {
+    /// Whether the name of an interface matches the text, ignoring
+    /// the case of the letters if `ignore_case` is set.
+    pub fn matches(&self, text: &str, name: &str, ignore_case: bool) -> bool {
+        let (text, name) = (fold(text, ignore_case), fold(name, ignore_case));
+
+        match self {
+            MatchMode::Exact => name == text,
+            MatchMode::Prefix => name.starts_with(&text),
+            MatchMode::Suffix => name.ends_with(&text),
+            MatchMode::Glob => glob_match(&text, &name),
+            MatchMode::Contains => name.contains(&text),
+        }
+    }
+}
+
+fn fold(text: &str, ignore_case: bool) -> String {
+    match ignore_case {
+        true => text.to_lowercase(),
+        false => text.to_string(),
+    }
+}
+
+/// Internal method to get the networks whose name matches the text,
+/// best match first. A name that is the text comes first. With
+/// `Prefix` and `Contains`, the ones that start with it come next,
+/// then the shorter names, so `eth` finds `eth0` before `veth1a2b`
+/// and `eth10`. Whatever is still tied is ordered by index and name,
+/// so the order never depends on the order the source listed the
+/// interfaces in.
+pub(crate) fn find_matches(
+    networks: Vec<Network>,
+    text: &str,
+    mode: MatchMode,
+    ignore_case: bool,
+) -> Vec<Network> {
+    let folded = fold(text, ignore_case);
+    let by_prefix = matches!(mode, MatchMode::Prefix | MatchMode::Contains);
+    let rank = |network: &Network| {
+        let name = fold(&network.name, ignore_case);
+        (
+            name != folded,
+            by_prefix && !name.starts_with(&folded),
+            name.chars().count(),
+            network.index.is_none(),
+            network.index,
+            network.name.clone(),
+        )
+    };
+
+    let mut networks = networks
+        .into_iter()
+        .filter(|network| mode.matches(text, &network.name, ignore_case))
+        .collect::<Vec<Network>>();
+    networks.sort_by_cached_key(rank);
+    networks.dedup_by(|a, b| a.name == b.name);
+
+    networks
+}
+
+/// Every address of an interface, including the primary one of
+/// networks that only have the text of it.
+pub(crate) fn addresses(network: &Network) -> Vec<IpAddr> {
+    let mut addresses = network.ip_addrs();
+    if let Some(inet) = network.inet_addr() {
+        if !addresses.contains(&IpAddr::V4(inet)) {
+            addresses.push(IpAddr::V4(inet));
+        }
+    }
+
+    addresses
+}
+
+/// Internal method to match a text against a shell glob, with `*`,
+/// `?` and `[...]` like `[a-z]` or `[!0-9]`.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let text = text.chars().collect::<Vec<char>>();
+
+    glob_match_at(&pattern, &text)
+}
+
+fn glob_match_at(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => (0..=text.len()).any(|i| glob_match_at(&pattern[1..], &text[i..])),
+        Some('?') => !text.is_empty() && glob_match_at(&pattern[1..], &text[1..]),
+        Some('[') => match (text.first(), glob_class(&pattern[1..])) {
+            (Some(x), Some((matches, length))) => {
+                matches(*x) && glob_match_at(&pattern[length + 1..], &text[1..])
+            }
+            // A `[` without a `]` is just a character.
+            (Some('['), None) => glob_match_at(&pattern[1..], &text[1..]),
+            _ => false,
+        },
+        Some(x) => text.first() == Some(x) && glob_match_at(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Reads a `[...]` class that starts after the `[`, returning what it
+/// matches and how many characters of the pattern it took, up to and
+/// including the `]`.
+fn glob_class(pattern: &[char]) -> Option<(impl Fn(char) -> bool + '_, usize)> {
+    let negated = pattern.first() == Some(&'!');
+    let start = negated as usize;
+    // A `]` right at the start is part of the class.
+    let end = start + 1 + pattern.get(start + 1..)?.iter().position(|x| *x == ']')?;
+    let class = &pattern[start..end];
+
+    let matches = move |x: char| {
+        let mut i = 0;
+        let mut found = false;
+        while i < class.len() {
+            if i + 2 < class.len() && class[i + 1] == '-' {
+                found |= (class[i]..=class[i + 2]).contains(&x);
+                i += 3;
+            } else {
+                found |= class[i] == x;
+                i += 1;
+            }
+        }
+        found != negated
+    };
+
+    Some((matches, end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fixture::network;
+    use crate::{FixtureBackend, InterfaceFlags, SysfsBackend};
+
+    fn fixture() -> FixtureBackend {
+        let up = InterfaceFlags::UP | InterfaceFlags::RUNNING;
+
+        FixtureBackend::of([
+            network("wlp2s0")
+                .index(3)
+                .inet("192.168.1.4")
+                .flags(up)
+                .mtu(1500),
+            network("enp3s0")
+                .index(2)
+                .inet("10.0.0.5")
+                .flags(up)
+                .mtu(9000)
+                .mac("52:54:00:12:34:56"),
+            network("lo").index(1).inet("127.0.0.1").flags(up).mtu(1500),
+            network("eth1").index(4).mtu(1500),
+            network("docker0")
+                .index(5)
+                .inet("172.17.0.1")
+                .flags(up)
+                .mtu(1500),
+            network("wlp2s0")
+                .index(3)
+                .inet("192.168.1.4")
+                .flags(up)
+                .mtu(1500),
+        ])
+        .with_default_routes(&["wlp2s0"])
+    }
+
+    fn names(networks: Vec<Network>) -> Vec<String> {
+        networks.into_iter().map(|x| x.name).collect()
+    }
+
+    #[test]
+    fn does_network_query_work() {
+        let backend = fixture();
+
+        assert_eq!(
+            names(NetworkQuery::new().run_with(&backend)),
+            ["lo", "enp3s0", "wlp2s0", "eth1", "docker0"]
+        );
+        assert_eq!(
+            names(
+                NetworkQuery::new()
+                    .up(true)
+                    .has_ipv4(true)
+                    .is_virtual(false)
+                    .run_with(&backend)
+            ),
+            ["enp3s0", "wlp2s0", "docker0"]
+        );
+        assert_eq!(
+            names(NetworkQuery::new().name_prefix("e").run_with(&backend)),
+            ["enp3s0", "eth1"]
+        );
+        assert_eq!(
+            names(
+                NetworkQuery::new()
+                    .name("eth1")
+                    .up(false)
+                    .run_with(&backend)
+            ),
+            ["eth1"]
+        );
+        assert_eq!(
+            names(
+                NetworkQuery::new()
+                    .name_regex(Regex::new("^(en|wl)").unwrap())
+                    .kind(InterfaceKind::Wireless)
+                    .run_with(&backend)
+            ),
+            ["wlp2s0"]
+        );
+        assert_eq!(
+            names(
+                NetworkQuery::new()
+                    .in_cidr("10.0.0.0/8".parse().unwrap())
+                    .mac_vendor([0x52, 0x54, 0x00])
+                    .mtu(9000..)
+                    .run_with(&backend)
+            ),
+            ["enp3s0"]
+        );
+        assert_eq!(
+            names(
+                NetworkQuery::new()
+                    .has_default_route(true)
+                    .run_with(&backend)
+            ),
+            ["wlp2s0"]
+        );
+        assert!(NetworkQuery::new()
+            .mtu(..1500)
+            .run_with(&backend)
+            .is_empty());
+    }
+
+    #[test]
+    fn does_network_query_read_default_routes() {
+        let backend = SysfsBackend::with_root("tests/fixtures/sysfs");
+        assert_eq!(backend.default_routes().unwrap(), ["br0"]);
+
+        let networks = NetworkQuery::new()
+            .has_default_route(true)
+            .has_ipv6(true)
+            .run_with(&backend);
+        assert_eq!(names(networks), ["br0"]);
+    }
+
+    #[test]
+    fn does_glob_match_work() {
+        assert!(glob_match("wl*", "wlp2s0"));
+        assert!(glob_match("*s0", "wlp2s0"));
+        assert!(glob_match("eth?", "eth0"));
+        assert!(!glob_match("eth?", "eth10"));
+        assert!(glob_match("eth[0-3]", "eth2"));
+        assert!(!glob_match("eth[0-3]", "eth4"));
+        assert!(glob_match("eth[!0-3]", "eth4"));
+        assert!(glob_match("br[]x]", "br]"));
+        assert!(glob_match("a[b", "a[b"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("lo", "lo0"));
+    }
+}
@@ -0,0 +1,218 @@
+/// # Interface Stats
+/// The traffic counters of a network interface, as printed by
+/// `ifconfig` in the `RX` and `TX` lines, or read from the kernel.
+/// Counters a source does not report are left at zero.
+/// * rx_packets, rx_bytes: What was received.
+/// * rx_errors, rx_dropped, rx_overruns, rx_frame, rx_missed: Why received packets were lost.
+/// * rx_compressed, multicast: Compressed and multicast packets received.
+/// * tx_packets, tx_bytes: What was sent.
+/// * tx_errors, tx_dropped, tx_overruns, tx_carrier, tx_collisions: Why sent packets were lost.
+/// * tx_compressed: Compressed packets sent.
+///
+/// The overruns are the FIFO errors of the kernel (`rx_fifo_errors`
+/// and `tx_fifo_errors`), which is what `ifconfig` prints as
+/// `overruns`, whatever source they are read from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct InterfaceStats {
+    pub rx_packets: u64,
+    pub rx_bytes: u64,
+    pub rx_errors: u64,
+    pub rx_dropped: u64,
+    pub rx_overruns: u64,
+    pub rx_frame: u64,
+    pub rx_missed: u64,
+    pub rx_compressed: u64,
+    pub multicast: u64,
+    pub tx_packets: u64,
+    pub tx_bytes: u64,
+    pub tx_errors: u64,
+    pub tx_dropped: u64,
+    pub tx_overruns: u64,
+    pub tx_carrier: u64,
+    pub tx_collisions: u64,
+    pub tx_compressed: u64,
+}
+
+impl InterfaceStats {
+    /// Reads the counters of an `RX` or `TX` line of `ifconfig`,
+    /// like `RX errors 0  dropped 0  overruns 0  frame 0`.
+    /// The older `RX packets:99 errors:0` form is understood too,
+    /// including its `RX bytes:6432 (6.2 KiB)  TX bytes:8809 (8.6 KiB)`
+    /// and `collisions:0 txqueuelen:1000` lines.
+    /// Returns false if the line is not one of them.
+    pub(crate) fn parse_ifconfig_line(&mut self, line: &str) -> bool {
+        let tokens = line.split_whitespace().collect::<Vec<&str>>();
+        let mut rx = match tokens.first() {
+            Some(&"RX") => true,
+            Some(&"TX") => false,
+            Some(x) if x.starts_with("collisions:") => false,
+            _ => return false,
+        };
+
+        for (i, token) in tokens.iter().enumerate() {
+            match *token {
+                "RX" => rx = true,
+                "TX" => rx = false,
+                _ => {}
+            }
+
+            let (key, value) = match token.split_once(':') {
+                Some((key, value)) => (key, value),
+                None => (*token, tokens.get(i + 1).copied().unwrap_or_default()),
+            };
+
+            if let (Ok(value), Some(counter)) = (value.parse::<u64>(), self.counter(rx, key)) {
+                *counter = value;
+            }
+        }
+
+        true
+    }
+
+    /// The counter `ifconfig` calls `key`, in the RX or TX direction.
+    fn counter(&mut self, rx: bool, key: &str) -> Option<&mut u64> {
+        let counter = match (rx, key) {
+            (_, "collisions") => &mut self.tx_collisions,
+            (true, "packets") => &mut self.rx_packets,
+            (true, "bytes") => &mut self.rx_bytes,
+            (true, "errors") => &mut self.rx_errors,
+            (true, "dropped") => &mut self.rx_dropped,
+            (true, "overruns") => &mut self.rx_overruns,
+            (true, "frame") => &mut self.rx_frame,
+            (false, "packets") => &mut self.tx_packets,
+            (false, "bytes") => &mut self.tx_bytes,
+            (false, "errors") => &mut self.tx_errors,
+            (false, "dropped") => &mut self.tx_dropped,
+            (false, "overruns") => &mut self.tx_overruns,
+            (false, "carrier") => &mut self.tx_carrier,
+            _ => return None,
+        };
+
+        Some(counter)
+    }
+}
+
+/// # Parse Proc Net Dev
+/// Parses the contents of `/proc/net/dev` into the counters
+/// of every network interface listed in it, in the same order.
+///
+/// # Arguments
+///
+/// * `text`: The contents of `/proc/net/dev`.
+///
+/// # Returns
+///
+/// `Vec<(String, InterfaceStats)>`: The name and counters of each interface.
+///
+/// # Example
+///
+/// ```
+/// use ip_extractor::parse_proc_net_dev;
+///
+/// let stats = parse_proc_net_dev("Inter-|   Receive                                                |  Transmit
+///  face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+///     lo: 4883937    1253    0    0    0     0          0         0  4883937    1253    0    0    0     0       0          0
+/// ");
+///
+/// assert_eq!(stats[0].0, "lo");
+/// assert_eq!(stats[0].1.rx_packets, 1253);
+/// ```
+pub fn parse_proc_net_dev(text: &str) -> Vec<(String, InterfaceStats)> {
+    text.lines()
+        .filter_map(|line| {
+            let (name, counters) = line.split_once(':')?;
+            let counters = counters
+                .split_whitespace()
+                .map(|x| x.parse::<u64>().ok())
+                .collect::<Option<Vec<u64>>>()?;
+            if counters.len() < 16 {
+                return None;
+            }
+
+            let stats = InterfaceStats {
+                rx_bytes: counters[0],
+                rx_packets: counters[1],
+                rx_errors: counters[2],
+                rx_dropped: counters[3],
+                rx_overruns: counters[4],
+                rx_frame: counters[5],
+                rx_compressed: counters[6],
+                multicast: counters[7],
+                tx_bytes: counters[8],
+                tx_packets: counters[9],
+                tx_errors: counters[10],
+                tx_dropped: counters[11],
+                tx_overruns: counters[12],
+                tx_collisions: counters[13],
+                tx_carrier: counters[14],
+                tx_compressed: counters[15],
+                ..Default::default()
+            };
+
+            Some((name.trim().to_string(), stats))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_parse_ifconfig_line_work() {
+        let mut stats = InterfaceStats::default();
+
+        assert!(stats.parse_ifconfig_line("RX packets 99  bytes 6432 (6.2 KiB)"));
+        assert!(stats.parse_ifconfig_line("RX errors 1  dropped 2  overruns 3  frame 4"));
+        assert!(stats.parse_ifconfig_line("TX packets 98  bytes 8809 (8.6 KiB)"));
+        assert!(
+            stats.parse_ifconfig_line("TX errors 5  dropped 6 overruns 7  carrier 8  collisions 9")
+        );
+        assert!(!stats.parse_ifconfig_line("inet 127.0.0.1  netmask 255.0.0.0"));
+
+        assert_eq!(stats.rx_packets, 99);
+        assert_eq!(stats.rx_bytes, 6432);
+        assert_eq!(stats.rx_frame, 4);
+        assert_eq!(stats.tx_packets, 98);
+        assert_eq!(stats.tx_overruns, 7);
+        assert_eq!(stats.tx_collisions, 9);
+    }
+
+    #[test]
+    fn does_parse_legacy_ifconfig_line_work() {
+        let mut stats = InterfaceStats::default();
+
+        assert!(stats.parse_ifconfig_line("RX packets:123 errors:1 dropped:2 overruns:3 frame:4"));
+        assert!(stats.parse_ifconfig_line("TX packets:65 errors:5 dropped:6 overruns:7 carrier:8"));
+        assert!(stats.parse_ifconfig_line("collisions:9 txqueuelen:1000"));
+        assert!(stats.parse_ifconfig_line("RX bytes:98765 (96.4 KiB)  TX bytes:12345 (12.0 KiB)"));
+
+        assert_eq!(stats.rx_packets, 123);
+        assert_eq!(stats.rx_frame, 4);
+        assert_eq!(stats.rx_bytes, 98765);
+        assert_eq!(stats.tx_packets, 65);
+        assert_eq!(stats.tx_carrier, 8);
+        assert_eq!(stats.tx_collisions, 9);
+        assert_eq!(stats.tx_bytes, 12345);
+    }
+
+    #[test]
+    fn does_parse_proc_net_dev_work() {
+        let stats = parse_proc_net_dev(
+            "Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 4883937    1253    0    0    0     0          0         0  4883937    1253    0    0    0     0       0          0
+  eth0:    6432      99    1    2    3     4          0         7     8809      98    5    6    0     9       8          0
+",
+        );
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[1].0, "eth0");
+        assert_eq!(stats[1].1.rx_bytes, 6432);
+        assert_eq!(stats[1].1.rx_frame, 4);
+        assert_eq!(stats[1].1.multicast, 7);
+        assert_eq!(stats[1].1.tx_packets, 98);
+        assert_eq!(stats[1].1.tx_collisions, 9);
+        assert_eq!(stats[1].1.tx_carrier, 8);
+    }
+}